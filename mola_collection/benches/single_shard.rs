@@ -0,0 +1,98 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use hashbrown::DefaultHashBuilder;
+use mola_collections::hash::concurrent::locked::LockedMap;
+use mola_collections::hash::concurrent::prelude::*;
+use mola_collections::hash::concurrent::rcu::HamtMap;
+
+const ENTRIES: u64 = 16;
+
+/// Compare the shard routing of `shard_for_key`, which skips hashing when
+/// there is a single shard, with the previous routing hashing every key.
+///
+/// Both maps are measured with one and with the default number of shards,
+/// so the cost of the single-shard check on sharded maps shows up too.
+fn bench_routing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shard_routing");
+    for shards in [1, 32] {
+        let rcu: HamtMap<u64, u64> =
+            HamtMap::with_shards_and_hasher(shards, DefaultHashBuilder::default());
+        let locked: LockedMap<u64, u64> =
+            LockedMap::with_shards_and_capacity_and_hasher(shards, 0, Default::default());
+
+        group.bench_with_input(BenchmarkId::new("rcu/hashed", shards), &rcu, |b, map| {
+            b.iter(|| {
+                for i in 0..ENTRIES {
+                    black_box(map.shard_for_hash(map.hash_key(&i)));
+                }
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("rcu/shard_for_key", shards),
+            &rcu,
+            |b, map| {
+                b.iter(|| {
+                    for i in 0..ENTRIES {
+                        black_box(map.shard_for_key(&i));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("locked/hashed", shards),
+            &locked,
+            |b, map| {
+                b.iter(|| {
+                    for i in 0..ENTRIES {
+                        black_box(map.shard_for_hash(map.hash_key(&i)));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("locked/shard_for_key", shards),
+            &locked,
+            |b, map| {
+                b.iter(|| {
+                    for i in 0..ENTRIES {
+                        black_box(map.shard_for_key(&i));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Compare a tiny RCU map using a single unpadded shard, which skips hashing
+/// to pick a shard, with the default sharded layout.
+fn bench_tiny_map(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tiny_rcu_map");
+    for shards in [1, 32] {
+        let map: HamtMap<u64, u64> =
+            HamtMap::with_shards_and_hasher(shards, DefaultHashBuilder::default());
+        for i in 0..ENTRIES {
+            map.insert(i, i);
+        }
+
+        group.bench_with_input(BenchmarkId::new("get", shards), &map, |b, map| {
+            b.iter(|| {
+                for i in 0..ENTRIES {
+                    black_box(map.get(&i));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("insert", shards), &map, |b, map| {
+            b.iter(|| {
+                for i in 0..ENTRIES {
+                    black_box(map.insert(i, i + 1));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_routing, bench_tiny_map);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use mola_collections::hash::concurrent::locked::LockedMap;
+use mola_collections::hash::concurrent::prelude::*;
+
+const ENTRIES: usize = 10_000;
+
+/// Insert and look up long string keys in the locked map. Growing a shard
+/// reuses the stored hashes instead of hashing every key again.
+fn bench_string_keys(c: &mut Criterion) {
+    let mut group = c.benchmark_group("locked_string_keys");
+    for key_len in [16, 256, 4096] {
+        let keys: Vec<String> = (0..ENTRIES).map(|i| format!("{i:0>key_len$}")).collect();
+
+        group.bench_with_input(BenchmarkId::new("insert", key_len), &keys, |b, keys| {
+            b.iter(|| {
+                let map = LockedMap::<String, usize>::new();
+                for (i, key) in keys.iter().enumerate() {
+                    map.insert(key.clone(), i);
+                }
+                black_box(map)
+            })
+        });
+
+        let map = LockedMap::<String, usize>::new();
+        for (i, key) in keys.iter().enumerate() {
+            map.insert(key.clone(), i);
+        }
+        group.bench_with_input(BenchmarkId::new("get", key_len), &keys, |b, keys| {
+            b.iter(|| {
+                for key in keys {
+                    black_box(map.get(key.as_str()));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_string_keys);
+criterion_main!(benches);
@@ -0,0 +1,141 @@
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use hashbrown::DefaultHashBuilder;
+
+/// A key stored together with its precomputed hash.
+///
+/// Hashing a `CachedHash` only feeds the cached value to the hasher, so
+/// large keys such as long byte strings are hashed once when wrapped instead
+/// of on every lookup. Use it as the key of a map built with a
+/// [`CachedHashBuilder`], and create it with [`CachedHashBuilder::cache`] so
+/// the hash comes from the same hasher as the map.
+///
+/// Equality compares the cached hashes first and the keys only when they
+/// match.
+#[derive(Debug, Clone)]
+pub struct CachedHash<K> {
+    hash: u64,
+    key: K,
+}
+
+impl<K> CachedHash<K> {
+    /// Wrap a key, hashing it once with the given hash builder.
+    ///
+    /// # Arguments
+    /// * `key` - The key to wrap
+    /// * `hash_builder` - The hash builder computing the cached hash
+    ///
+    /// # Returns
+    /// The key together with its hash
+    pub fn new<S: BuildHasher>(key: K, hash_builder: &S) -> Self
+    where
+        K: Hash,
+    {
+        Self {
+            hash: hash_builder.hash_one(&key),
+            key,
+        }
+    }
+
+    /// Get the cached hash.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Get the wrapped key.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Consume the wrapper and return the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<K> Hash for CachedHash<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl<K: PartialEq> PartialEq for CachedHash<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.key == other.key
+    }
+}
+
+impl<K: Eq> Eq for CachedHash<K> {}
+
+/// A hash builder for maps keyed by [`CachedHash`].
+///
+/// The hashers it builds pass the cached hash through unchanged. The inner
+/// hash builder is only used by [`cache`](Self::cache) to compute the hash
+/// of new keys.
+#[derive(Debug, Clone, Default)]
+pub struct CachedHashBuilder<S = DefaultHashBuilder> {
+    inner: S,
+}
+
+impl<S: BuildHasher> CachedHashBuilder<S> {
+    /// Create a new builder computing hashes with the given hash builder.
+    ///
+    /// # Arguments
+    /// * `inner` - The hash builder computing the cached hashes
+    ///
+    /// # Returns
+    /// A new builder instance
+    pub fn with_hasher(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Wrap a key, hashing it once with the inner hash builder.
+    ///
+    /// # Arguments
+    /// * `key` - The key to wrap
+    ///
+    /// # Returns
+    /// The key together with its hash
+    pub fn cache<K: Hash>(&self, key: K) -> CachedHash<K> {
+        CachedHash::new(key, &self.inner)
+    }
+}
+
+impl<S> BuildHasher for CachedHashBuilder<S> {
+    type Hasher = PassthroughHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        PassthroughHasher { hash: 0 }
+    }
+}
+
+/// A hasher returning the last `u64` written to it.
+///
+/// Other writes are folded in with a cheap multiplicative mix, which keeps
+/// non-[`CachedHash`] keys working but gives them a poor distribution.
+#[derive(Debug, Clone, Copy)]
+pub struct PassthroughHasher {
+    hash: u64,
+}
+
+// Multiplier of the fallback byte mix.
+const MIX: u64 = 0x517C_C1B7_2722_0A95;
+
+impl Hasher for PassthroughHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.hash = (self.hash.rotate_left(5) ^ byte as u64).wrapping_mul(MIX);
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, n: u64) {
+        self.hash = n;
+    }
+}
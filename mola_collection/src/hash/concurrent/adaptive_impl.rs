@@ -0,0 +1,211 @@
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::DefaultHashBuilder;
+use spin::RwLock;
+
+use super::locked_impl::LockedMap;
+use super::traits::{RawHashMap, ReadableInPlaceMap};
+
+// Default number of shards a new map starts with. Must be a power of two.
+const DEFAULT_INITIAL_SHARDS: usize = 4;
+
+// Default number of entries per shard above which the map grows.
+const DEFAULT_MAX_LOAD: usize = 1024;
+
+// Default upper bound on the number of shards. Must be a power of two.
+const DEFAULT_MAX_SHARDS: usize = 256;
+
+/// A locked concurrent map that grows its number of shards with its size.
+///
+/// Once the number of entries exceeds `max_load` times the number of shards,
+/// the shard count is doubled until the load fits again, up to `max_shards`.
+///
+/// Growing is rare but expensive: every entry is drained and reinserted into
+/// a new map while a map-wide write lock is held, blocking all readers and
+/// writers for the duration. Regular operations only take that lock shared,
+/// on top of the lock of their shard.
+pub struct AdaptiveLockedMap<K, V, S = DefaultHashBuilder> {
+    map: RwLock<LockedMap<K, V, S>>,
+    max_load: usize,
+    max_shards: usize,
+}
+
+impl<K, V> AdaptiveLockedMap<K, V>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+    /// Create a new adaptive map with default settings.
+    ///
+    /// # Returns
+    /// A new adaptive map instance
+    pub fn new() -> Self {
+        Self::with_settings_and_hasher(
+            DEFAULT_INITIAL_SHARDS,
+            DEFAULT_MAX_LOAD,
+            DEFAULT_MAX_SHARDS,
+            Default::default(),
+        )
+    }
+}
+
+impl<K, V> Default for AdaptiveLockedMap<K, V>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> AdaptiveLockedMap<K, V, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Clone + Send + Sync,
+{
+    /// Create a new adaptive map with custom settings.
+    ///
+    /// # Arguments
+    /// * `initial_shards` - The number of shards to start with (must be a power of two)
+    /// * `max_load` - The number of entries per shard above which the map grows
+    /// * `max_shards` - The maximum number of shards (must be a power of two)
+    /// * `hash_builder` - The hash builder to use
+    ///
+    /// # Returns
+    /// A new adaptive map instance
+    ///
+    /// # Panics
+    /// Panics if `initial_shards` or `max_shards` is not a power of two, or
+    /// if `max_load` is zero
+    pub fn with_settings_and_hasher(
+        initial_shards: usize,
+        max_load: usize,
+        max_shards: usize,
+        hash_builder: S,
+    ) -> Self {
+        assert!(
+            max_shards.is_power_of_two(),
+            "Maximum number of shards must be a power of two"
+        );
+        assert!(max_load > 0, "Maximum load must be positive");
+        Self {
+            map: RwLock::new(LockedMap::with_shards_and_capacity_and_hasher(
+                initial_shards,
+                0,
+                hash_builder,
+            )),
+            max_load,
+            max_shards,
+        }
+    }
+
+    /// Get the number of shards that fits `len` entries.
+    fn target_shards(&self, len: usize, shards: usize) -> usize {
+        let mut target = shards;
+        while target < self.max_shards && len > self.max_load * target {
+            target *= 2;
+        }
+        target
+    }
+
+    /// Rebuild the map with more shards if it is still overloaded.
+    fn grow(&self) {
+        let mut map = self.map.write();
+        let target = self.target_shards(map.len(), map.shard_count());
+        if target > map.shard_count() {
+            *map = map.reshard(target);
+        }
+    }
+
+    /// Insert a key-value pair, growing the shard count if needed.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let (old, overloaded) = {
+            let map = self.map.read();
+            let old = map.insert(key, value).and_then(|old| old.try_owned());
+            let shards = map.shard_count();
+            (old, self.target_shards(map.len(), shards) > shards)
+        };
+        if overloaded {
+            self.grow();
+        }
+        old
+    }
+
+    /// Get a clone of the value associated with the key.
+    ///
+    /// # Returns
+    /// A clone of the value, if the key exists
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+    {
+        self.view(key, |_, v| v.clone())
+    }
+
+    /// Run a closure on the entry associated with the key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the key and value references
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    pub fn view<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&K, &V) -> R,
+    {
+        self.map.read().view(key, f)
+    }
+
+    /// Remove a key from the map.
+    ///
+    /// The shard count never shrinks.
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.read().remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Check if a key exists in the map.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.read().contains_key(key)
+    }
+
+    /// Get the current number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.map.read().shard_count()
+    }
+
+    /// Get the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.read().len()
+    }
+
+    /// Check if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.read().is_empty()
+    }
+}
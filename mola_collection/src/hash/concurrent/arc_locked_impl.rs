@@ -0,0 +1,147 @@
+use alloc::sync::Arc;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::DefaultHashBuilder;
+
+use super::locked_impl::{LockedMap, DEFAULT_SHARDS};
+use super::traits::{RawHashMap, ReadableInPlaceMap, ReadableMap};
+use super::wrapper::MaybeArc;
+
+/// A locked concurrent map storing its values behind an `Arc`.
+///
+/// [`LockedMap::get`](ReadableMap::get) clones the value under the read
+/// lock and returns it as `MaybeArc::Owned`. This variant stores `Arc<V>`
+/// instead, so `get` only bumps a reference count and returns
+/// `MaybeArc::Shared`, like the RCU map does. It suits large values that are
+/// replaced rather than mutated in place, and does not require `V: Clone`.
+pub struct ArcLockedMap<K, V, S = DefaultHashBuilder> {
+    map: LockedMap<K, Arc<V>, S>,
+}
+
+impl<K, V> ArcLockedMap<K, V>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+    /// Create a new Arc-backed locked map with default settings.
+    ///
+    /// # Returns
+    /// A new, empty map
+    pub fn new() -> Self {
+        Self::with_hasher(Default::default())
+    }
+}
+
+impl<K, V> Default for ArcLockedMap<K, V>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> ArcLockedMap<K, V, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    /// Create a new Arc-backed locked map with a custom hash builder.
+    ///
+    /// # Arguments
+    /// * `hash_builder` - The hash builder to use
+    ///
+    /// # Returns
+    /// A new, empty map
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: LockedMap::with_shards_and_capacity_and_hasher(DEFAULT_SHARDS, 0, hash_builder),
+        }
+    }
+
+    /// Insert a value that is already shared, without allocating a new `Arc`.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The shared value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any
+    pub fn insert_arc(&self, key: K, value: Arc<V>) -> Option<Arc<V>> {
+        self.map.insert(key, value).map(MaybeArc::into_owned)
+    }
+}
+
+impl<K, V, S> RawHashMap<K, V> for ArcLockedMap<K, V, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    fn insert(&self, key: K, value: V) -> Option<MaybeArc<V>> {
+        self.insert_arc(key, Arc::new(value)).map(MaybeArc::Shared)
+    }
+
+    fn remove<Q>(&self, key: &Q) -> Option<MaybeArc<V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map
+            .remove(key)
+            .map(|old| MaybeArc::Shared(old.into_owned()))
+    }
+
+    fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.contains_key(key)
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<K, V, S> ReadableMap<K, V> for ArcLockedMap<K, V, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    fn get<Q>(&self, key: &Q) -> Option<MaybeArc<V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        // Only the Arc is cloned under the read lock, never the value
+        self.map.view(key, |_, v| MaybeArc::Shared(Arc::clone(v)))
+    }
+}
+
+impl<K, V, S> ReadableInPlaceMap<K, V> for ArcLockedMap<K, V, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    type ReadResult<R> = Option<R>;
+
+    fn view<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&K, &V) -> R,
+    {
+        self.map.view(key, |k, v| f(k, v.as_ref()))
+    }
+}
@@ -0,0 +1,303 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use hashbrown::DefaultHashBuilder;
+
+use super::locked_impl::{LockedMap, DEFAULT_SHARDS};
+use super::traits::{RawHashMap, ReadableInPlaceMap};
+
+// Number of rows of the count-min sketch.
+const SKETCH_DEPTH: usize = 4;
+
+// Seeds used to derive an independent counter index for every row.
+const SKETCH_SEEDS: [u64; SKETCH_DEPTH] = [
+    0x9E37_79B9_7F4A_7C15,
+    0xC2B2_AE3D_27D4_EB4F,
+    0x1656_67B1_9E37_79F9,
+    0x85EB_CA77_C2B2_AE63,
+];
+
+// Number of entries examined when looking for an eviction candidate.
+const EVICTION_SAMPLE: usize = 16;
+
+// Number of recorded accesses per counter of a row between two decays.
+const DECAY_PERIOD: usize = 10;
+
+/// A count-min sketch estimating how often each hash was recorded.
+///
+/// Estimates never undercount the accesses since the last decay, but hash
+/// collisions may overcount. Every `10 * width` recorded accesses, all
+/// counters are halved, so that keys that used to be hot do not stay
+/// protected from eviction forever.
+pub struct CountMinSketch {
+    counters: Box<[AtomicU32]>,
+    mask: usize,
+    /// Accesses recorded since the last decay
+    additions: AtomicUsize,
+}
+
+impl CountMinSketch {
+    /// Create a new sketch with the given number of counters per row.
+    ///
+    /// # Arguments
+    /// * `width` - The number of counters per row (rounded up to a power of two)
+    ///
+    /// # Returns
+    /// A new sketch with all counters at zero
+    pub fn with_width(width: usize) -> Self {
+        let width = width.max(1).next_power_of_two();
+        let counters = (0..width * SKETCH_DEPTH)
+            .map(|_| AtomicU32::new(0))
+            .collect::<Vec<_>>();
+        Self {
+            counters: counters.into_boxed_slice(),
+            mask: width - 1,
+            additions: AtomicUsize::new(0),
+        }
+    }
+
+    /// Get the counter of the given row for a hash.
+    fn counter(&self, row: usize, hash: u64) -> &AtomicU32 {
+        let mixed = (hash ^ SKETCH_SEEDS[row]).wrapping_mul(SKETCH_SEEDS[0]);
+        let index = (mixed >> 32) as usize & self.mask;
+        &self.counters[row * (self.mask + 1) + index]
+    }
+
+    /// Record one access of a hash, decaying the sketch periodically.
+    pub fn increment(&self, hash: u64) {
+        for row in 0..SKETCH_DEPTH {
+            let counter = self.counter(row, hash);
+            let _ =
+                counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |c| c.checked_add(1));
+        }
+
+        // Only the thread reaching the period decays, later additions are kept
+        let period = (self.mask + 1) * DECAY_PERIOD;
+        if self.additions.fetch_add(1, Ordering::Relaxed) + 1 == period {
+            self.additions.fetch_sub(period, Ordering::Relaxed);
+            self.decay();
+        }
+    }
+
+    /// Halve every counter of the sketch.
+    pub fn decay(&self) {
+        for counter in self.counters.iter() {
+            let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |c| Some(c / 2));
+        }
+    }
+
+    /// Estimate how many times a hash was recorded.
+    pub fn estimate(&self, hash: u64) -> u32 {
+        (0..SKETCH_DEPTH)
+            .map(|row| self.counter(row, hash).load(Ordering::Relaxed))
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+// Default number of sketch counters per row.
+const DEFAULT_SKETCH_WIDTH: usize = 1024;
+
+/// A locked concurrent map holding a bounded number of entries.
+///
+/// Accesses are recorded in an optional count-min sketch. When a new key is
+/// inserted into a full map, a sample of the entries is examined and the one
+/// with the lowest estimated access frequency is evicted. Without a sketch,
+/// an arbitrary sampled entry is evicted.
+///
+/// The bound is approximate: concurrent inserts may briefly exceed it.
+pub struct BoundedLockedMap<K, V, S = DefaultHashBuilder> {
+    map: LockedMap<K, V, S>,
+    capacity: usize,
+    sketch: Option<CountMinSketch>,
+    /// The shard to start the next eviction sample at, advanced round-robin
+    eviction_cursor: AtomicUsize,
+}
+
+impl<K, V> BoundedLockedMap<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+{
+    /// Create a new bounded map with a frequency sketch of the default size.
+    ///
+    /// # Arguments
+    /// * `capacity` - The maximum number of entries
+    ///
+    /// # Returns
+    /// A new bounded map instance
+    pub fn new(capacity: usize) -> Self {
+        Self::with_sketch_width_and_hasher(capacity, Some(DEFAULT_SKETCH_WIDTH), Default::default())
+    }
+}
+
+impl<K, V, S> BoundedLockedMap<K, V, S>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    /// Create a new bounded map with custom settings.
+    ///
+    /// # Arguments
+    /// * `capacity` - The maximum number of entries
+    /// * `sketch_width` - The number of sketch counters per row, or `None` to
+    ///   disable frequency tracking
+    /// * `hash_builder` - The hash builder to use
+    ///
+    /// # Returns
+    /// A new bounded map instance
+    pub fn with_sketch_width_and_hasher(
+        capacity: usize,
+        sketch_width: Option<usize>,
+        hash_builder: S,
+    ) -> Self {
+        Self {
+            map: LockedMap::with_shards_and_capacity_and_hasher(DEFAULT_SHARDS, 0, hash_builder),
+            capacity,
+            sketch: sketch_width.map(CountMinSketch::with_width),
+            eviction_cursor: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<K, V, S> BoundedLockedMap<K, V, S>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    /// Record an access of the key in the sketch, if any.
+    fn record<Q: ?Sized + Hash>(&self, key: &Q) {
+        if let Some(sketch) = &self.sketch {
+            sketch.increment(self.map.hash_key(key));
+        }
+    }
+
+    /// Estimate how many times the key was accessed.
+    ///
+    /// # Returns
+    /// The estimated access count, or 0 if frequency tracking is disabled
+    pub fn frequency<Q: ?Sized + Hash>(&self, key: &Q) -> u32 {
+        self.sketch
+            .as_ref()
+            .map_or(0, |sketch| sketch.estimate(self.map.hash_key(key)))
+    }
+
+    /// Find the sampled entry with the lowest estimated access frequency.
+    ///
+    /// Each sample starts at the shard after the one the previous sample
+    /// started at, so evictions are spread over all shards.
+    fn eviction_candidate(&self) -> Option<K> {
+        let shards = &self.map.storage.shards;
+        let mask = shards.len() - 1;
+        let start = self.eviction_cursor.fetch_add(1, Ordering::Relaxed);
+        let mut candidate: Option<(u32, K)> = None;
+        let mut sampled = 0;
+        for offset in 0..shards.len() {
+            let table = shards[start.wrapping_add(offset) & mask].table.read();
+            for (_, k, _) in table.iter() {
+                let frequency = self.frequency(k);
+                if candidate.as_ref().is_none_or(|(min, _)| frequency < *min) {
+                    candidate = Some((frequency, k.clone()));
+                }
+                sampled += 1;
+                if sampled >= EVICTION_SAMPLE {
+                    return candidate.map(|(_, k)| k);
+                }
+            }
+        }
+        candidate.map(|(_, k)| k)
+    }
+
+    /// Insert a key-value pair, evicting an entry if the map is full.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.record(&key);
+        if !self.map.contains_key(&key) && self.map.len() >= self.capacity {
+            if let Some(victim) = self.eviction_candidate() {
+                self.map.remove(&victim);
+            }
+        }
+        self.map.insert(key, value).and_then(|old| old.try_owned())
+    }
+
+    /// Get a clone of the value associated with the key, recording the access.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    /// A clone of the value, if the key exists
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+    {
+        self.view(key, |_, v| v.clone())
+    }
+
+    /// Run a closure on the entry associated with the key, recording the access.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the key and value references
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    pub fn view<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&K, &V) -> R,
+    {
+        self.record(key);
+        self.map.view(key, f)
+    }
+
+    /// Remove a key from the map.
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Check if a key exists, without recording an access.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Get the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
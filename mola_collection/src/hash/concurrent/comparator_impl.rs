@@ -0,0 +1,154 @@
+use hashbrown::hash_table::Entry;
+
+use super::locked_impl::{DEFAULT_SHARDS, LockedStorage};
+use super::traits::ShardStorage;
+
+/// A user-supplied notion of key equality, replacing `K: Hash + Eq`.
+///
+/// Keys that compare equal must produce the same hash.
+pub trait KeyComparator<K>: Send + Sync {
+    /// Compute the hash of a key.
+    fn hash(&self, key: &K) -> u64;
+
+    /// Check if two keys are equal.
+    fn eq(&self, a: &K, b: &K) -> bool;
+}
+
+/// A locked concurrent map whose keys are hashed and compared by a
+/// [`KeyComparator`] instead of their own `Hash` and `Eq` implementations.
+///
+/// This allows context-dependent equality, e.g. case-insensitive strings.
+/// When two keys compare equal, the first inserted key is kept. Entries are
+/// kept in a [`LockedStorage`], sharded and counted like a locked map.
+pub struct ComparatorLockedMap<K, V, C> {
+    storage: LockedStorage<K, V>,
+    comparator: C,
+}
+
+impl<K, V, C> ComparatorLockedMap<K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: KeyComparator<K>,
+{
+    /// Create a new map with the default number of shards.
+    ///
+    /// # Arguments
+    /// * `comparator` - The comparator used to hash and compare keys
+    ///
+    /// # Returns
+    /// A new map instance
+    pub fn with_comparator(comparator: C) -> Self {
+        Self::with_shards_and_comparator(DEFAULT_SHARDS, comparator)
+    }
+
+    /// Create a new map with custom settings.
+    ///
+    /// # Arguments
+    /// * `shards` - The number of shards (must be a power of two)
+    /// * `comparator` - The comparator used to hash and compare keys
+    ///
+    /// # Returns
+    /// A new map instance
+    ///
+    /// # Panics
+    /// Panics if `shards` is not a power of two
+    pub fn with_shards_and_comparator(shards: usize, comparator: C) -> Self {
+        Self {
+            storage: LockedStorage::with_shards_and_capacity(shards, 0),
+            comparator,
+        }
+    }
+
+    /// Get the comparator of the map.
+    pub fn comparator(&self) -> &C {
+        &self.comparator
+    }
+
+    /// Insert a key-value pair.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with an equal key, if any
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let hash = self.comparator.hash(&key);
+        let mut table = self.storage.shard_for_hash(hash).table.write();
+        let entry = table.entry(
+            hash,
+            |(h, k, _)| *h == hash && self.comparator.eq(k, &key),
+            |(h, _, _)| *h,
+        );
+
+        match entry {
+            Entry::Occupied(mut occ) => Some(core::mem::replace(&mut occ.get_mut().2, value)),
+            Entry::Vacant(vac) => {
+                vac.insert((hash, key, value));
+                self.storage.shard_increment(1);
+                None
+            }
+        }
+    }
+
+    /// Run a closure on the entry associated with the key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the stored key and value references
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    pub fn view<F, R>(&self, key: &K, f: F) -> Option<R>
+    where
+        F: FnOnce(&K, &V) -> R,
+    {
+        let hash = self.comparator.hash(key);
+        let table = self.storage.shard_for_hash(hash).table.read();
+        table
+            .find(hash, |(h, k, _)| *h == hash && self.comparator.eq(k, key))
+            .map(|(_, k, v)| f(k, v))
+    }
+
+    /// Get a clone of the value associated with the key.
+    ///
+    /// # Returns
+    /// A clone of the value, if the key exists
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.view(key, |_, v| v.clone())
+    }
+
+    /// Remove a key from the map.
+    ///
+    /// # Returns
+    /// The stored key and its value, if an equal key existed
+    pub fn remove(&self, key: &K) -> Option<(K, V)> {
+        let hash = self.comparator.hash(key);
+        let mut table = self.storage.shard_for_hash(hash).table.write();
+        let entry = table
+            .find_entry(hash, |(h, k, _)| *h == hash && self.comparator.eq(k, key))
+            .ok()?;
+        let ((_, k, v), _) = entry.remove();
+        self.storage.shard_decrement(1);
+        Some((k, v))
+    }
+
+    /// Check if an equal key exists in the map.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.view(key, |_, _| ()).is_some()
+    }
+
+    /// Get the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.storage.shard_len()
+    }
+
+    /// Check if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.storage.shard_is_empty()
+    }
+}
@@ -0,0 +1,100 @@
+use core::hash::Hash;
+
+use super::traits::{RawHashMap, ReadableInPlaceMap, ReadableMap};
+use super::wrapper::MaybeArc;
+
+/// An object-safe subset of the concurrent map operations.
+///
+/// The map traits use generic methods and generic associated types, so they
+/// cannot be used as trait objects. This trait keeps the operations that
+/// can, and is implemented for every readable map, so maps of different
+/// backends can be stored as `Box<dyn DynConcurrentMap<K, V>>`.
+///
+/// Its methods share their names with the map traits, so avoid importing
+/// both in the same scope when calling methods on a concrete map.
+pub trait DynConcurrentMap<K, V>: Send + Sync {
+    /// Insert a key-value pair into the map.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any
+    fn insert(&self, key: K, value: V) -> Option<MaybeArc<V>>;
+
+    /// Remove a key-value pair from the map.
+    ///
+    /// # Arguments
+    /// * `key` - The key to remove
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed
+    fn remove(&self, key: &K) -> Option<MaybeArc<V>>;
+
+    /// Check if a key exists in the map.
+    fn contains_key(&self, key: &K) -> bool;
+
+    /// Get a value from the map.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    /// The value, if the key exists
+    fn get(&self, key: &K) -> Option<MaybeArc<V>>;
+
+    /// Run a closure on the entry associated with the key.
+    ///
+    /// The closure cannot return a value through a trait object, so results
+    /// are passed out through its captures.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the key and value references
+    ///
+    /// # Returns
+    /// True if the key exists and the closure ran, false otherwise
+    fn view(&self, key: &K, f: &mut dyn FnMut(&K, &V)) -> bool;
+
+    /// Get the number of entries in the map.
+    fn len(&self) -> usize;
+
+    /// Check if the map is empty.
+    fn is_empty(&self) -> bool;
+}
+
+impl<K, V, M> DynConcurrentMap<K, V> for M
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    M: ReadableMap<K, V> + ReadableInPlaceMap<K, V>,
+{
+    fn insert(&self, key: K, value: V) -> Option<MaybeArc<V>> {
+        RawHashMap::insert(self, key, value)
+    }
+
+    fn remove(&self, key: &K) -> Option<MaybeArc<V>> {
+        RawHashMap::remove(self, key)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        RawHashMap::contains_key(self, key)
+    }
+
+    fn get(&self, key: &K) -> Option<MaybeArc<V>> {
+        ReadableMap::get(self, key)
+    }
+
+    fn view(&self, key: &K, f: &mut dyn FnMut(&K, &V)) -> bool {
+        ReadableInPlaceMap::view(self, key, f).is_some()
+    }
+
+    fn len(&self) -> usize {
+        RawHashMap::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        RawHashMap::is_empty(self)
+    }
+}
@@ -0,0 +1,189 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::{DefaultHashBuilder, HashSet};
+
+use super::locked_impl::{LockedMap, DEFAULT_SHARDS};
+use super::traits::{MutableInPlaceMap, RawHashMap, ReadableInPlaceMap};
+
+/// A locked concurrent map with a secondary index over its values.
+///
+/// The index maps the key extracted from every value to the set of primary
+/// keys holding such a value, and is kept up to date by
+/// [`insert_indexed`](Self::insert_indexed) and
+/// [`remove_indexed`](Self::remove_indexed). The index is updated while the
+/// shard of the primary key is still write-locked, so concurrent writers to
+/// the same key cannot leave it stale. Values are only exposed read-only,
+/// since changing them in place would bypass the index.
+///
+/// Locks are always taken on the primary map first and on the index second.
+pub struct SecondaryIndex<K, V, IK, E, S = DefaultHashBuilder> {
+    primary: LockedMap<K, V, S>,
+    index: LockedMap<IK, HashSet<K>, S>,
+    extract: E,
+}
+
+impl<K, V, IK, E> SecondaryIndex<K, V, IK, E>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+    IK: Hash + Eq + Send + Sync,
+    E: Fn(&V) -> IK,
+{
+    /// Create a new indexed map.
+    ///
+    /// # Arguments
+    /// * `extract` - A closure computing the index key of a value
+    ///
+    /// # Returns
+    /// A new, empty indexed map
+    pub fn new(extract: E) -> Self {
+        Self::with_hasher(extract, Default::default())
+    }
+}
+
+impl<K, V, IK, E, S> SecondaryIndex<K, V, IK, E, S>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+    IK: Hash + Eq + Send + Sync,
+    E: Fn(&V) -> IK,
+    S: BuildHasher + Clone + Send + Sync,
+{
+    /// Create a new indexed map with a custom hash builder.
+    ///
+    /// # Arguments
+    /// * `extract` - A closure computing the index key of a value
+    /// * `hash_builder` - The hash builder used by the map and its index
+    ///
+    /// # Returns
+    /// A new, empty indexed map
+    pub fn with_hasher(extract: E, hash_builder: S) -> Self {
+        Self {
+            primary: LockedMap::with_shards_and_capacity_and_hasher(
+                DEFAULT_SHARDS,
+                0,
+                hash_builder.clone(),
+            ),
+            index: LockedMap::with_shards_and_capacity_and_hasher(DEFAULT_SHARDS, 0, hash_builder),
+            extract,
+        }
+    }
+
+    /// Insert a key-value pair and index it.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any
+    pub fn insert_indexed(&self, key: K, value: V) -> Option<V> {
+        let index_key = (self.extract)(&value);
+        self.primary.shard_session(&key, |session| {
+            let old = session.insert(key.clone(), value);
+            if let Some(old_index_key) = old.as_ref().map(&self.extract) {
+                if old_index_key == index_key {
+                    return old;
+                }
+                self.unindex(&old_index_key, &key);
+            }
+            self.index.alter_entry(index_key, HashSet::new, |keys| {
+                keys.insert(key);
+            });
+            old
+        })
+    }
+
+    /// Remove a key and drop it from the index.
+    ///
+    /// # Arguments
+    /// * `key` - The key to remove
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed
+    pub fn remove_indexed<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.primary.shard_session(key, |session| {
+            let value = session.remove(key)?;
+            self.unindex(&(self.extract)(&value), key);
+            Some(value)
+        })
+    }
+
+    /// Remove a primary key from the set of an index key.
+    ///
+    /// Sets left empty are removed from the index.
+    fn unindex<Q>(&self, index_key: &IK, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.index.shard_session(index_key, |session| {
+            let emptied = session.get_mut(index_key).is_some_and(|keys| {
+                keys.remove(key);
+                keys.is_empty()
+            });
+            if emptied {
+                session.remove(index_key);
+            }
+        });
+    }
+
+    /// Get the primary keys whose value has the given index key.
+    ///
+    /// # Arguments
+    /// * `index_key` - The index key to look up
+    ///
+    /// # Returns
+    /// The matching primary keys, in no particular order
+    pub fn lookup<Q>(&self, index_key: &Q) -> Vec<K>
+    where
+        IK: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.index
+            .view(index_key, |_, keys| keys.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Run a closure on the entry associated with the key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the key and value references
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    pub fn view<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&K, &V) -> R,
+    {
+        self.primary.view(key, f)
+    }
+
+    /// Check if the key exists in the map.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.primary.contains_key(key)
+    }
+
+    /// Get the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.primary.len()
+    }
+
+    /// Check if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.primary.is_empty()
+    }
+}
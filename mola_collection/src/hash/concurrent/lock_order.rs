@@ -0,0 +1,77 @@
+//! Debug tracking of the order in which a thread locks shards.
+//!
+//! Operations locking several shards of a map at once must lock them in
+//! ascending index order, otherwise two threads can deadlock each other. In
+//! debug builds, every such lock is recorded for the current thread along
+//! with the storage it belongs to, and taking a shard with an index not above
+//! all shards already held in the same storage panics. Shards of different
+//! storages are not ordered against each other. Otherwise the tracking
+//! compiles to nothing.
+//!
+//! The record is a thread local, so it needs the standard library: it is
+//! kept in debug builds with the `std` feature, and in debug test builds.
+
+#[cfg(all(debug_assertions, any(test, feature = "std")))]
+extern crate std;
+
+#[cfg(all(debug_assertions, any(test, feature = "std")))]
+use alloc::vec::Vec;
+#[cfg(all(debug_assertions, any(test, feature = "std")))]
+use core::cell::RefCell;
+
+#[cfg(all(debug_assertions, any(test, feature = "std")))]
+std::thread_local! {
+    static HELD: RefCell<Vec<(usize, usize)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A shard lock recorded for the current thread until dropped.
+pub(crate) struct LockOrderToken {
+    #[cfg(all(debug_assertions, any(test, feature = "std")))]
+    shard: (usize, usize),
+}
+
+impl LockOrderToken {
+    /// Record that the current thread is about to lock a shard.
+    ///
+    /// # Arguments
+    /// * `storage` - The address of the storage owning the shard
+    /// * `index` - The index of the shard in the storage
+    ///
+    /// # Panics
+    /// Panics in debug builds if the thread already holds a shard of the
+    /// same storage with an index greater than or equal to `index`
+    #[cfg_attr(
+        not(all(debug_assertions, any(test, feature = "std"))),
+        allow(unused_variables)
+    )]
+    pub(crate) fn acquire(storage: usize, index: usize) -> Self {
+        #[cfg(all(debug_assertions, any(test, feature = "std")))]
+        HELD.with(|held| {
+            let mut held = held.borrow_mut();
+            let same_storage = held.iter().filter(|(s, _)| *s == storage);
+            if let Some(max) = same_storage.map(|&(_, i)| i).max() {
+                assert!(
+                    index > max,
+                    "Shard {index} locked while holding shard {max}, shards must be locked in ascending order"
+                );
+            }
+            held.push((storage, index));
+        });
+        Self {
+            #[cfg(all(debug_assertions, any(test, feature = "std")))]
+            shard: (storage, index),
+        }
+    }
+}
+
+#[cfg(all(debug_assertions, any(test, feature = "std")))]
+impl Drop for LockOrderToken {
+    fn drop(&mut self) {
+        HELD.with(|held| {
+            let mut held = held.borrow_mut();
+            if let Some(position) = held.iter().rposition(|&shard| shard == self.shard) {
+                held.swap_remove(position);
+            }
+        });
+    }
+}
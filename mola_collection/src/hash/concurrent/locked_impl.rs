@@ -1,19 +1,29 @@
-use alloc::boxed::Box;
+use alloc::borrow::Cow;
+use alloc::rc::Rc;
 use alloc::vec::Vec;
 use core::borrow::Borrow;
 use core::hash::{BuildHasher, Hash};
 use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
 use core::sync::atomic::{AtomicUsize, Ordering};
 
-use crossbeam_utils::CachePadded;
-use hashbrown::DefaultHashBuilder;
+use hashbrown::{DefaultHashBuilder, HashSet};
 use hashbrown::Equivalent;
 use hashbrown::hash_table::{Entry, HashTable};
-use spin::RwLock;
+// The shard lock spins by default, keeping the map `no_std`, and parks
+// contended threads with the `parking_lot` feature.
+#[cfg(feature = "parking_lot")]
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(not(feature = "parking_lot"))]
+use spin::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::hash::concurrent::wrapper::MaybeArc;
+use crate::hash::fixed::FixedMap;
+use crate::hash::seeded::SeededHashBuilder;
 
-use super::traits::{MutableMap, ReadableMap, RawHashMap, ShardStorage, MutableGuard, MutableInPlaceMap, ReadableInPlaceMap};
+use super::lock_order::LockOrderToken;
+use super::shards::Shards;
+use super::traits::{MutableMap, ReadableMap, RawHashMap, ShardStorage, MutableGuard, MutableInPlaceMap, ReadableInPlaceMap, CounterValue};
 use super::wrapper::ConcurrentMap;
 
 /// A dummy guard for locked concurrent map since it doesn't support mutable guards.
@@ -71,11 +81,192 @@ where
         })
         .unwrap_or(Err(()))
     }
+
+    fn into_inner(self) -> V {
+        self.value
+    }
+}
+
+/// A read reference to a value, keeping its shard read-locked while alive.
+///
+/// Writers to the shard are blocked until the reference is dropped.
+pub struct LockedReadRef<'a, K, V> {
+    _guard: RwLockReadGuard<'a, HashTable<(u64, K, V)>>,
+    value: NonNull<V>,
+}
+
+impl<K, V> LockedReadRef<'_, K, V> {
+    /// Borrow the value as a [`Cow`] without cloning it.
+    ///
+    /// The borrow is tied to this reference, so the shard stays read-locked
+    /// for as long as the returned `Cow::Borrowed` is alive.
+    pub fn as_cow(&self) -> Cow<'_, V>
+    where
+        V: Clone,
+    {
+        Cow::Borrowed(&**self)
+    }
+}
+
+impl<K, V> Deref for LockedReadRef<'_, K, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.value.as_ref() }
+    }
+}
+
+/// A shard write lock taken as part of a multi-shard operation.
+///
+/// The lock is recorded by the debug lock order tracker while held.
+struct OrderedWriteGuard<'a, K, V> {
+    guard: RwLockWriteGuard<'a, HashTable<(u64, K, V)>>,
+    _token: LockOrderToken,
+}
+
+impl<K, V> Deref for OrderedWriteGuard<'_, K, V> {
+    type Target = HashTable<(u64, K, V)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<K, V> DerefMut for OrderedWriteGuard<'_, K, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+/// A write reference to a value, keeping its shard write-locked while alive.
+///
+/// References to values in the same shard share a single write lock, which
+/// is released once all of them are dropped.
+pub struct LockedWriteRef<'a, K, V> {
+    _guard: Rc<OrderedWriteGuard<'a, K, V>>,
+    value: NonNull<V>,
+}
+
+impl<K, V> Deref for LockedWriteRef<'_, K, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<K, V> DerefMut for LockedWriteRef<'_, K, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.value.as_mut() }
+    }
+}
+
+/// A batch of operations on a single shard, performed under one write lock.
+///
+/// Every key passed to the session must belong to the locked shard.
+pub struct ShardSession<'a, K, V, S> {
+    map: &'a LockedMap<K, V, S>,
+    shard: &'a LockedShard<K, V>,
+    table: &'a mut HashTable<(u64, K, V)>,
+}
+
+impl<K, V, S> ShardSession<'_, K, V, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    /// Hash a key and check that it belongs to the locked shard.
+    fn hash_in_shard<Q: ?Sized + Hash>(&self, key: &Q) -> u64 {
+        let hash = self.map.hash_key(key);
+        debug_assert!(
+            core::ptr::eq(self.map.storage.shard_for_hash(hash), self.shard),
+            "Key does not belong to the shard of this session"
+        );
+        hash
+    }
+
+    /// Get a mutable reference to the value associated with a key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    /// A mutable reference to the value, if the key exists
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let hash = self.hash_in_shard(key);
+        self.table
+            .find_mut(hash, |(h, k, _)| *h == hash && key.equivalent(k))
+            .map(|(_, _, v)| v)
+    }
+
+    /// Insert a key-value pair into the shard.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = self.hash_in_shard(&key);
+        let map = self.map;
+        match self.table.entry(
+            hash,
+            |(h, k_ref, _)| *h == hash && k_ref == &key,
+            |(h, _, _)| *h,
+        ) {
+            Entry::Occupied(mut occ) => Some(core::mem::replace(&mut occ.get_mut().2, value)),
+            Entry::Vacant(vac) => {
+                vac.insert((hash, key, value));
+                map.storage.shard_increment(1);
+                None
+            }
+        }
+    }
+
+    /// Remove a key from the shard.
+    ///
+    /// # Arguments
+    /// * `key` - The key to remove
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let hash = self.hash_in_shard(key);
+        let entry = self
+            .table
+            .find_entry(hash, |(h, k, _)| *h == hash && key.equivalent(k))
+            .ok()?;
+        let ((_, _, v), _) = entry.remove();
+        self.map.storage.shard_decrement(1);
+        Some(v)
+    }
 }
 
 /// A single shard of the locked hash table.
+///
+/// Entries are stored as `(hash, key, value)`. The hash computed when the
+/// entry is inserted is reused whenever the table grows, and compared before
+/// the keys on lookups, so keys that are expensive to hash or compare are
+/// never rehashed, at the cost of 8 bytes per entry.
+///
+/// Every read takes the shard read lock. An optimistic, seqlock-style read
+/// that validates a write generation afterwards is not an option here: a
+/// concurrent insert may grow the table and free the buckets the reader is
+/// probing, so the reader could touch freed memory before it ever gets to
+/// check the generation. Read-heavy workloads that cannot afford the lock
+/// should use the RCU map, whose readers never block or retry.
 pub struct LockedShard<K, V> {
-    pub(crate) table: RwLock<HashTable<(K, V)>>,
+    pub(crate) table: RwLock<HashTable<(u64, K, V)>>,
 }
 
 impl<K, V> LockedShard<K, V> {
@@ -101,13 +292,31 @@ impl<K, V> Default for LockedShard<K, V> {
     }
 }
 
+/// How a locked map keeps track of its number of entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CountingMode {
+    /// Maintain a shared atomic counter, making `len()` O(1).
+    #[default]
+    Atomic,
+    /// Keep no counter and sum the length of every shard on demand.
+    ///
+    /// `len()` becomes O(shards) and takes each shard's read lock, but it is
+    /// always derived from the authoritative shard contents.
+    Summed,
+}
+
 /// Storage implementation for locked concurrent hash maps.
 ///
 /// This storage uses spin-based read-write locks to protect each shard,
 /// providing thread-safe access with good performance characteristics.
+/// With the `parking_lot` feature, shards use `parking_lot::RwLock` instead,
+/// which parks contended threads rather than spinning.
 pub struct LockedStorage<K, V> {
-    shards: Box<[CachePadded<LockedShard<K, V>>]>,
-    count: AtomicUsize,
+    pub(crate) shards: Shards<LockedShard<K, V>>,
+    /// The entry counter, absent in [`CountingMode::Summed`]
+    count: Option<AtomicUsize>,
+    /// The shard to sample next, advanced round-robin
+    sample_cursor: AtomicUsize,
 }
 
 impl<K, V> LockedStorage<K, V> {
@@ -123,23 +332,144 @@ impl<K, V> LockedStorage<K, V> {
     /// # Panics
     /// Panics if `shards` is not a power of two
     pub fn with_shards_and_capacity(shards: usize, capacity: usize) -> Self {
+        Self::with_shards_capacity_and_padding(shards, capacity, true)
+    }
+
+    /// Create new locked storage with the given shard layout.
+    ///
+    /// The shards are laid out padded or packed right away, see
+    /// [`with_cache_padding`](Self::with_cache_padding).
+    ///
+    /// # Arguments
+    /// * `shards` - The number of shards (must be a power of two)
+    /// * `capacity` - The initial capacity per shard
+    /// * `padded` - Whether to pad each shard to its own cache line
+    ///
+    /// # Returns
+    /// A new locked storage instance
+    ///
+    /// # Panics
+    /// Panics if `shards` is not a power of two
+    pub fn with_shards_capacity_and_padding(shards: usize, capacity: usize, padded: bool) -> Self {
         assert!(
             shards.is_power_of_two(),
             "Number of shards must be a power of two"
         );
-        let mut shard_vec = Vec::with_capacity(shards);
-        for _ in 0..shards {
-            shard_vec.push(CachePadded::new(LockedShard::with_capacity(capacity)));
+        Self {
+            shards: Shards::new(
+                (0..shards).map(|_| LockedShard::with_capacity(capacity)),
+                padded,
+            ),
+            count: Some(AtomicUsize::new(0)),
+            sample_cursor: AtomicUsize::new(0),
         }
+    }
+
+    /// Create locked storage from already built shard tables.
+    ///
+    /// Entries are stored as `(hash, key, value)`, where `hash` is the hash of
+    /// the key computed by the hash builder of the map. The storage has no
+    /// hash builder to check it with. [`LockedMap::from_shards`] takes
+    /// `(key, value)` tables and computes the hashes itself.
+    ///
+    /// # Arguments
+    /// * `tables` - The table of every shard (length must be a power of two)
+    ///
+    /// # Returns
+    /// A new locked storage instance owning the tables
+    ///
+    /// # Panics
+    /// Panics if the number of tables is not a power of two
+    pub fn from_tables(tables: Vec<HashTable<(u64, K, V)>>) -> Self {
+        assert!(
+            tables.len().is_power_of_two(),
+            "Number of shards must be a power of two"
+        );
+        let count = tables.iter().map(HashTable::len).sum();
+        let shards = tables.into_iter().map(|table| LockedShard {
+            table: RwLock::new(table),
+        });
         Self {
-            shards: shard_vec.into_boxed_slice(),
-            count: AtomicUsize::new(0),
+            shards: Shards::padded(shards),
+            count: Some(AtomicUsize::new(count)),
+            sample_cursor: AtomicUsize::new(0),
+        }
+    }
+
+    /// Set how the storage counts its entries.
+    ///
+    /// # Arguments
+    /// * `mode` - The counting mode to use
+    ///
+    /// # Returns
+    /// The storage instance for method chaining
+    ///
+    /// # Panics
+    /// Panics if the storage already contains entries
+    pub fn with_counting_mode(mut self, mode: CountingMode) -> Self {
+        assert!(
+            self.shards.iter().all(|shard| shard.table.read().is_empty()),
+            "Counting mode must be set before inserting entries"
+        );
+        self.count = match mode {
+            CountingMode::Atomic => Some(AtomicUsize::new(0)),
+            CountingMode::Summed => None,
+        };
+        self
+    }
+
+    /// Set whether each shard is padded to its own cache line.
+    ///
+    /// Shards are padded by default, so that threads working on neighbouring
+    /// shards do not contend on the same cache line. Without padding the
+    /// shards are packed, which saves memory on constrained targets with
+    /// many shards at the cost of some false sharing.
+    ///
+    /// # Arguments
+    /// * `padded` - Whether to pad the shards
+    ///
+    /// # Returns
+    /// The storage instance for method chaining
+    pub fn with_cache_padding(mut self, padded: bool) -> Self {
+        self.shards = self.shards.with_cache_padding(padded);
+        self
+    }
+
+    /// Check if each shard is padded to its own cache line.
+    pub fn is_cache_padded(&self) -> bool {
+        self.shards.is_padded()
+    }
+
+    /// Get the number of bytes taken by the shard slice, padding included.
+    ///
+    /// The tables owned by the shards are not included.
+    pub fn shards_size_in_bytes(&self) -> usize {
+        self.shards.size_in_bytes()
+    }
+
+    /// Get the counting mode of the storage.
+    pub fn counting_mode(&self) -> CountingMode {
+        if self.count.is_some() {
+            CountingMode::Atomic
+        } else {
+            CountingMode::Summed
         }
     }
+
+    /// Read the entry counter with relaxed ordering.
+    ///
+    /// This is a cheap diagnostic read that may be slightly stale, unlike
+    /// `len()` which reads the counter with acquire ordering.
+    ///
+    /// # Returns
+    /// The counter value, or `None` in [`CountingMode::Summed`]
+    pub fn raw_count(&self) -> Option<usize> {
+        self.count.as_ref().map(|count| count.load(Ordering::Relaxed))
+    }
 }
 
 // Default number of shards. Must be a power of two.
-const DEFAULT_SHARDS: usize = 32;
+pub(crate) const DEFAULT_SHARDS: usize = 32;
 
 impl<K, V> Default for LockedStorage<K, V> {
     fn default() -> Self {
@@ -154,7 +484,7 @@ where
 {
     type Shard = LockedShard<K, V>;
 
-    fn shard_for_hash(&self, hash: u64) -> &CachePadded<Self::Shard> {
+    fn shard_for_hash(&self, hash: u64) -> &Self::Shard {
         &self.shards[hash as usize & (self.shards.len() - 1)]
     }
 
@@ -163,15 +493,26 @@ where
     }
 
     fn shard_increment(&self, num: usize) {
-        self.count.fetch_add(num, Ordering::AcqRel);
+        if let Some(count) = &self.count {
+            count.fetch_add(num, Ordering::AcqRel);
+        }
     }
 
     fn shard_decrement(&self, num: usize) {
-        self.count.fetch_sub(num, Ordering::AcqRel);
+        if let Some(count) = &self.count {
+            count.fetch_sub(num, Ordering::AcqRel);
+        }
     }
 
     fn shard_len(&self) -> usize {
-        self.count.load(Ordering::Acquire)
+        match &self.count {
+            Some(count) => count.load(Ordering::Acquire),
+            None => self
+                .shards
+                .iter()
+                .map(|shard| shard.table.read().len())
+                .sum(),
+        }
     }
 
     fn shard_is_empty(&self) -> bool {
@@ -196,6 +537,46 @@ where
     pub fn new() -> Self {
         Self::with_shards_and_capacity_and_hasher(DEFAULT_SHARDS, 0, Default::default())
     }
+
+    /// Create a new locked concurrent map pre-sized from a sample of keys.
+    ///
+    /// See [`with_capacity_hints_and_hasher`](Self::with_capacity_hints_and_hasher).
+    ///
+    /// # Arguments
+    /// * `sample` - A sample of the keys expected to be inserted
+    ///
+    /// # Returns
+    /// A new, empty locked concurrent map instance
+    pub fn with_capacity_hints<I>(sample: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+    {
+        Self::with_capacity_hints_and_hasher(sample, Default::default())
+    }
+}
+
+impl<K, V> LockedMap<K, V, SeededHashBuilder>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+    /// Create a new locked concurrent map hashing with a fixed seed.
+    ///
+    /// Maps created with the same seed place the same keys in the same
+    /// shards and iterate them in the same order on every run.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed of the hash builder
+    ///
+    /// # Returns
+    /// A new locked concurrent map instance
+    pub fn with_seeded_hasher(seed: u64) -> Self {
+        Self::with_shards_and_capacity_and_hasher(
+            DEFAULT_SHARDS,
+            0,
+            SeededHashBuilder::with_seed(seed),
+        )
+    }
 }
 
 impl<K, V, S> LockedMap<K, V, S>
@@ -224,6 +605,69 @@ where
         let storage = LockedStorage::with_shards_and_capacity(shards, capacity);
         ConcurrentMap::with_storage_and_hasher(storage, hash_builder)
     }
+
+    /// Create a new locked concurrent map from already partitioned shard tables.
+    ///
+    /// Entries are not moved between tables, so every entry must be stored in
+    /// the table at index `hash as usize & (shards.len() - 1)`, with the hash
+    /// computed by `hash_builder`. Otherwise lookups will miss it. The hash of
+    /// every key is computed here and stored next to the entry.
+    ///
+    /// # Arguments
+    /// * `shards` - The table of every shard (length must be a power of two)
+    /// * `hash_builder` - The hash builder used to partition the tables
+    ///
+    /// # Returns
+    /// A new locked concurrent map instance owning the tables
+    ///
+    /// # Panics
+    /// Panics if the number of tables is not a power of two
+    pub fn from_shards(shards: Vec<HashTable<(K, V)>>, hash_builder: S) -> Self {
+        let mask = shards.len().wrapping_sub(1);
+        let tables = shards
+            .into_iter()
+            .enumerate()
+            .map(|(index, table)| {
+                let mut hashed = HashTable::with_capacity(table.len());
+                for (key, value) in table {
+                    let hash = hash_builder.hash_one(&key);
+                    debug_assert_eq!(
+                        hash as usize & mask,
+                        index,
+                        "Entry stored in the wrong shard"
+                    );
+                    hashed.insert_unique(hash, (hash, key, value), |(h, _, _)| *h);
+                }
+                hashed
+            })
+            .collect();
+        ConcurrentMap::with_storage_and_hasher(LockedStorage::from_tables(tables), hash_builder)
+    }
+
+    /// Create a new locked concurrent map pre-sized from a sample of keys.
+    ///
+    /// Every key of the sample is hashed to find its shard, and each shard
+    /// reserves room for as many entries as sample keys it received. With a
+    /// skewed but predictable workload, the busy shards start large and
+    /// rehash less often. The sample keys are not inserted.
+    ///
+    /// # Arguments
+    /// * `sample` - A sample of the keys expected to be inserted
+    /// * `hash_builder` - The hash builder to use
+    ///
+    /// # Returns
+    /// A new, empty locked concurrent map instance
+    pub fn with_capacity_hints_and_hasher<I>(sample: I, hash_builder: S) -> Self
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut counts = [0usize; DEFAULT_SHARDS];
+        for key in sample {
+            counts[hash_builder.hash_one(&key) as usize & (DEFAULT_SHARDS - 1)] += 1;
+        }
+        let tables = counts.into_iter().map(HashTable::with_capacity).collect();
+        Self::from_shards(tables, hash_builder)
+    }
 }
 
 impl<K, V, S> Default for LockedMap<K, V, S>
@@ -248,15 +692,19 @@ where
         let shard = self.storage.shard_for_hash(hash);
         let mut table = shard.table.write();
 
-        let entry = table.entry(hash, |(k_ref, _)| k_ref == &key, |(k, _)| self.hash_key(k));
+        let entry = table.entry(
+            hash,
+            |(h, k_ref, _)| *h == hash && k_ref == &key,
+            |(h, _, _)| *h,
+        );
 
         match entry {
             Entry::Occupied(mut occ) => Some(MaybeArc::Owned(core::mem::replace(
-                &mut occ.get_mut().1,
+                &mut occ.get_mut().2,
                 value,
             ))),
             Entry::Vacant(vac) => {
-                vac.insert((key, value));
+                vac.insert((hash, key, value));
                 self.storage.shard_increment(1);
                 None
             }
@@ -271,8 +719,8 @@ where
         let hash = self.hash_key(key);
         let shard = self.storage.shard_for_hash(hash);
         let mut table = shard.table.write();
-        if let Ok(entry) = table.find_entry(hash, |(k, _v)| key.equivalent(k)) {
-            let ((_, v), _) = entry.remove();
+        if let Ok(entry) = table.find_entry(hash, |(h, k, _)| *h == hash && key.equivalent(k)) {
+            let ((_, _, v), _) = entry.remove();
             self.storage.shard_decrement(1);
             Some(MaybeArc::Owned(v))
         } else {
@@ -288,7 +736,9 @@ where
         let hash = self.hash_key(key);
         let shard = self.storage.shard_for_hash(hash);
         let table = shard.table.read();
-        table.find(hash, |(k, _v)| key.equivalent(k)).is_some()
+        table
+            .find(hash, |(h, k, _)| *h == hash && key.equivalent(k))
+            .is_some()
     }
 
     fn len(&self) -> usize {
@@ -344,10 +794,9 @@ where
         let shard = self.storage.shard_for_hash(hash);
         let table = shard.table.read();
 
-        table.find(hash, |(k, _)| k.borrow() == key).map(|bucket| {
-            let (k, v) = bucket;
-            f(k, v)
-        })
+        table
+            .find(hash, |(h, k, _)| *h == hash && k.borrow() == key)
+            .map(|(_, k, v)| f(k, v))
     }
 }
 
@@ -387,10 +836,8 @@ where
         let mut table = shard.table.write();
 
         table
-            .find_mut(hash, |(k, _)| k.borrow() == key)
-            .map(|bucket| {
-                f(&mut bucket.1)
-            })
+            .find_mut(hash, |(h, k, _)| *h == hash && k.borrow() == key)
+            .map(|(_, _, v)| f(v))
     }
 
     fn alter_entry<F, D>(&self, key: K, default: D, f: F)
@@ -402,16 +849,20 @@ where
         let shard = self.storage.shard_for_hash(hash);
         let mut table = shard.table.write();
 
-        let entry = table.entry(hash, |(k_ref, _)| k_ref == &key, |(k, _)| self.hash_key(k));
+        let entry = table.entry(
+            hash,
+            |(h, k_ref, _)| *h == hash && k_ref == &key,
+            |(h, _, _)| *h,
+        );
 
         match entry {
             Entry::Occupied(mut occ) => {
-                f(&mut occ.get_mut().1);
+                f(&mut occ.get_mut().2);
             }
             Entry::Vacant(vac) => {
                 let mut value = default();
                 f(&mut value);
-                vac.insert((key, value));
+                vac.insert((hash, key, value));
                 self.storage.shard_increment(1);
             }
         }
@@ -432,11 +883,22 @@ where
             guard
                 .iter()
                 .next()
-                .map(|(k, v)| (k.clone(), MaybeArc::Owned(v.clone())))
+                .map(|(_, k, v)| (k.clone(), MaybeArc::Owned(v.clone())))
         })
     }
 }
 
+/// A summary of how entries are spread across the shards of a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardDistribution {
+    /// The number of entries in the least loaded shard
+    pub min: usize,
+    /// The number of entries in the most loaded shard
+    pub max: usize,
+    /// The number of shards holding no entries
+    pub empty_shards: usize,
+}
+
 // Add view method directly to LockedConcurrentMap for compatibility
 impl<K, V, S> LockedMap<K, V, S>
 where
@@ -459,8 +921,8 @@ where
         let hash = self.hash_key(key);
         let shard = self.storage.shard_for_hash(hash);
         let mut table = shard.table.write();
-        if let Ok(entry) = table.find_entry(hash, |(k, _v)| key.equivalent(k)) {
-            let ((k, v), _) = entry.remove();
+        if let Ok(entry) = table.find_entry(hash, |(h, k, _)| *h == hash && key.equivalent(k)) {
+            let ((_, k, v), _) = entry.remove();
             self.storage.shard_decrement(1);
             Some((k, v))
         } else {
@@ -468,6 +930,38 @@ where
         }
     }
 
+    /// Remove the key and shrink its shard if it became sparsely occupied.
+    ///
+    /// After removing the key, if the shard holds fewer entries than
+    /// `min_load` times its capacity, the shard table is shrunk to fit under
+    /// the write lock already held for the removal.
+    ///
+    /// # Arguments
+    /// * `key` - The key to remove
+    /// * `min_load` - The fraction of the capacity below which the shard is shrunk
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed
+    pub fn remove_and_maybe_shrink<Q>(&self, key: &Q, min_load: f32) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let hash = self.hash_key(key);
+        let shard = self.storage.shard_for_hash(hash);
+        let mut table = shard.table.write();
+        let ((_, _, v), _) = table
+            .find_entry(hash, |(h, k, _)| *h == hash && key.equivalent(k))
+            .ok()?
+            .remove();
+        self.storage.shard_decrement(1);
+
+        if (table.len() as f32) < table.capacity() as f32 * min_load {
+            table.shrink_to_fit(|(h, _, _)| *h);
+        }
+        Some(v)
+    }
+
     /// Clear all entries from the map.
     pub fn clear(&self) {
         for shard in self.storage.shards.iter() {
@@ -476,40 +970,967 @@ where
             table.clear();
         }
     }
-}
 
-// Builder pattern support
-pub struct LockedMapBuilder<S = DefaultHashBuilder> {
-    shards: usize,
-    capacity: usize,
-    hash_builder: Option<S>,
-}
-
-impl<S> Default for LockedMapBuilder<S>
-where
-    S: BuildHasher + Default + Send + Sync,
- {
-    fn default() -> Self {
-        Self::new()
+    /// Clear all entries from the map and release the memory of the shards.
+    ///
+    /// Unlike [`clear`](Self::clear), which keeps the capacity of every shard
+    /// for reuse, each shard table is replaced by a fresh empty one under its
+    /// write lock. Useful for long-lived maps that are occasionally reset.
+    pub fn clear_and_shrink(&self) {
+        for shard in self.storage.shards.iter() {
+            // Free the old table after releasing the lock
+            let old = {
+                let mut table = shard.table.write();
+                self.storage.shard_decrement(table.len());
+                core::mem::take(&mut *table)
+            };
+            drop(old);
+        }
     }
-}
 
-impl<S> LockedMapBuilder<S>
-where
-    S: BuildHasher + Default + Send + Sync,
-{
-    /// Create a new builder with default settings.
+    /// Remove and return all entries, leaving fresh empty shards behind.
+    ///
+    /// Each shard table is swapped for an empty one under its write lock, and
+    /// only moved out of afterwards, so producers keep writing to the new
+    /// shards while the old contents are collected. Shards are taken one at a
+    /// time, so the result is not a snapshot across shards.
     ///
     /// # Returns
-    /// A new builder instance
-    pub fn new() -> Self {
-        Self {
-            shards: DEFAULT_SHARDS,
-            capacity: 0,
-            hash_builder: None,
+    /// All entries that were in the map
+    pub fn take_all(&self) -> Vec<(K, V)> {
+        let mut entries = Vec::new();
+        for shard in self.storage.shards.iter() {
+            let old = {
+                let mut table = shard.table.write();
+                self.storage.shard_decrement(table.len());
+                core::mem::take(&mut *table)
+            };
+            entries.extend(old.into_iter().map(|(_, k, v)| (k, v)));
         }
+        entries
     }
-    /// Set a custom hasher for the map.
+
+    /// Check if all the keys exist in the map.
+    ///
+    /// The keys are grouped by shard, so each shard is read-locked at most
+    /// once. Each shard is checked under its own lock, so the result is not
+    /// a snapshot across shards.
+    ///
+    /// # Arguments
+    /// * `keys` - The keys to check for
+    ///
+    /// # Returns
+    /// True if every key exists, false as soon as one is missing
+    pub fn contains_all<Q>(&self, keys: &[&Q]) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.keys_by_shard(keys)
+            .chunk_by(|a, b| a.0 == b.0)
+            .all(|group| {
+                let table = self.storage.shards[group[0].0].table.read();
+                group.iter().all(|(_, hash, key)| {
+                    table
+                        .find(*hash, |(h, k, _)| h == hash && (*key).equivalent(k))
+                        .is_some()
+                })
+            })
+    }
+
+    /// Check if any of the keys exists in the map.
+    ///
+    /// The keys are grouped by shard, so each shard is read-locked at most
+    /// once.
+    ///
+    /// # Arguments
+    /// * `keys` - The keys to check for
+    ///
+    /// # Returns
+    /// True as soon as one key exists, false if none does
+    pub fn contains_any<Q>(&self, keys: &[&Q]) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.keys_by_shard(keys)
+            .chunk_by(|a, b| a.0 == b.0)
+            .any(|group| {
+                let table = self.storage.shards[group[0].0].table.read();
+                group.iter().any(|(_, hash, key)| {
+                    table
+                        .find(*hash, |(h, k, _)| h == hash && (*key).equivalent(k))
+                        .is_some()
+                })
+            })
+    }
+
+    /// Get a value along with the number of entries in its shard.
+    ///
+    /// Both are read under the same shard read lock, so the length is
+    /// consistent with the lookup. Useful for per-shard load decisions
+    /// without a second lookup.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    /// The value and the length of its shard, if the key exists
+    pub fn get_with_shard_len<Q>(&self, key: &Q) -> Option<(MaybeArc<V>, usize)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+    {
+        let hash = self.hash_key(key);
+        let shard = self.storage.shard_for_hash(hash);
+        let table = shard.table.read();
+
+        table
+            .find(hash, |(h, k, _)| *h == hash && k.borrow() == key)
+            .map(|(_, _, v)| (MaybeArc::Owned(v.clone()), table.len()))
+    }
+
+    /// Insert clones of every entry of a [`FixedMap`].
+    ///
+    /// The entries are grouped by shard, and each group is inserted under a
+    /// single write lock. Existing keys take the value of the fixed map.
+    ///
+    /// # Arguments
+    /// * `fixed` - The map to copy the entries from
+    pub fn apply_fixed<const CAP: usize, S2>(&self, fixed: &FixedMap<K, V, CAP, S2>)
+    where
+        K: Clone,
+        V: Clone,
+        S2: BuildHasher,
+    {
+        for group in self.entries_by_shard(fixed).chunk_by(|a, b| a.0 == b.0) {
+            let mut table = self.storage.shards[group[0].0].table.write();
+            let mut inserted = 0;
+            for &(_, hash, key, value) in group {
+                match table.entry(hash, |(h, k, _)| *h == hash && k == key, |(h, _, _)| *h) {
+                    Entry::Occupied(mut occ) => occ.get_mut().2 = value.clone(),
+                    Entry::Vacant(vac) => {
+                        vac.insert((hash, key.clone(), value.clone()));
+                        inserted += 1;
+                    }
+                }
+            }
+            self.storage.shard_increment(inserted);
+        }
+    }
+
+    /// Replace every value equal to `old` with a clone of `new`.
+    ///
+    /// Each shard is updated under its write lock, so all matching entries of a
+    /// shard change together.
+    ///
+    /// # Arguments
+    /// * `old` - The value to look for
+    /// * `new` - The value to store instead
+    ///
+    /// # Returns
+    /// The number of replaced values
+    pub fn replace_all(&self, old: &V, new: V) -> usize
+    where
+        V: PartialEq + Clone,
+    {
+        let mut replaced = 0;
+        for shard in self.storage.shards.iter() {
+            let mut table = shard.table.write();
+            for (_, _, v) in table.iter_mut() {
+                if v == old {
+                    *v = new.clone();
+                    replaced += 1;
+                }
+            }
+        }
+        replaced
+    }
+
+    /// Remove every entry whose key starts with a prefix.
+    ///
+    /// Hashing does not preserve prefixes, so every shard is scanned, each
+    /// under its own write lock. The removal is not atomic across shards.
+    ///
+    /// # Arguments
+    /// * `prefix` - The prefix of the keys to remove
+    ///
+    /// # Returns
+    /// The number of removed entries
+    pub fn remove_prefix(&self, prefix: &str) -> usize
+    where
+        K: Borrow<str>,
+    {
+        self.remove_keys_where(|k| k.borrow().starts_with(prefix))
+    }
+
+    /// Keep only the entries whose key is in a set.
+    ///
+    /// This is the bulk reconcile primitive against an authoritative key
+    /// set. Every shard is scanned, and the removal is not atomic across
+    /// shards.
+    ///
+    /// # Arguments
+    /// * `keep` - The keys to keep
+    ///
+    /// # Returns
+    /// The number of removed entries
+    pub fn retain_keys_in<Q, S2>(&self, keep: &HashSet<Q, S2>) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash,
+        S2: BuildHasher,
+    {
+        self.remove_keys_where(|k| !keep.contains(k.borrow()))
+    }
+
+    /// Remove the entries whose key is in a set.
+    ///
+    /// Every shard is scanned, and the removal is not atomic across shards.
+    ///
+    /// # Arguments
+    /// * `drop` - The keys to remove
+    ///
+    /// # Returns
+    /// The number of removed entries
+    pub fn remove_keys_in<Q, S2>(&self, drop: &HashSet<Q, S2>) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash,
+        S2: BuildHasher,
+    {
+        self.remove_keys_where(|k| drop.contains(k.borrow()))
+    }
+
+    /// Remove the entries whose key matches a predicate, one shard at a time.
+    fn remove_keys_where<F>(&self, pred: F) -> usize
+    where
+        F: Fn(&K) -> bool,
+    {
+        let mut removed = 0;
+        for shard in self.storage.shards.iter() {
+            let mut table = shard.table.write();
+            let before = table.len();
+            table.retain(|(_, k, _)| !pred(k));
+            let count = before - table.len();
+            self.storage.shard_decrement(count);
+            removed += count;
+        }
+        removed
+    }
+
+    /// Get a read reference to the value associated with the key.
+    ///
+    /// The shard is read-locked until the reference is dropped, so it should
+    /// be short-lived. Use [`LockedReadRef::as_cow`] to hand the value out as
+    /// a `Cow::Borrowed` without cloning it.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    /// A read reference to the value, if the key exists
+    pub fn get_ref<Q>(&self, key: &Q) -> Option<LockedReadRef<'_, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let hash = self.hash_key(key);
+        let shard = self.storage.shard_for_hash(hash);
+        let guard = shard.table.read();
+        let value = guard
+            .find(hash, |(h, k, _)| *h == hash && key.equivalent(k))
+            .map(|(_, _, v)| NonNull::from(v))?;
+        Some(LockedReadRef {
+            _guard: guard,
+            value,
+        })
+    }
+
+    /// Run a closure on the value associated with the key as a [`Cow`].
+    ///
+    /// The value is handed out as `Cow::Borrowed` without cloning it. The
+    /// borrow is tied to the read guard of the shard, which is held until the
+    /// closure returns, so writers to the shard wait for it. Use
+    /// [`get_ref`](Self::get_ref) to keep the guard across several reads.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the borrowed value
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    pub fn get_cow<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+        F: FnOnce(Cow<'_, V>) -> R,
+    {
+        let value = self.get_ref(key)?;
+        Some(f(value.as_cow()))
+    }
+
+    /// Write-lock a shard as part of a multi-shard operation.
+    ///
+    /// Shards must be locked in ascending index order, which is checked by
+    /// the lock order tracker in debug builds.
+    fn write_shard_ordered(&self, index: usize) -> OrderedWriteGuard<'_, K, V> {
+        let storage = &self.storage as *const LockedStorage<K, V> as usize;
+        let token = LockOrderToken::acquire(storage, index);
+        OrderedWriteGuard {
+            guard: self.storage.shards[index].table.write(),
+            _token: token,
+        }
+    }
+
+    /// Move the value of a key to another key, replacing any value there.
+    ///
+    /// The shards of both keys are write-locked together, in ascending index
+    /// order as in [`LockedMap::get_disjoint_mut`], so readers see the value
+    /// under exactly one of the keys, even across shards.
+    ///
+    /// # Arguments
+    /// * `from` - The key to move the value from
+    /// * `to` - The key to move the value to
+    ///
+    /// # Returns
+    /// True if the value was moved, false if `from` does not exist
+    pub fn rename<Q>(&self, from: &Q, to: K) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let from_hash = self.hash_key(from);
+        let to_hash = self.hash_key(&to);
+        let mask = self.storage.shards.len() - 1;
+        let from_index = from_hash as usize & mask;
+        let to_index = to_hash as usize & mask;
+
+        let (mut from_table, mut to_table) = match from_index.cmp(&to_index) {
+            core::cmp::Ordering::Equal => (self.write_shard_ordered(from_index), None),
+            core::cmp::Ordering::Less => {
+                let from_table = self.write_shard_ordered(from_index);
+                let to_table = self.write_shard_ordered(to_index);
+                (from_table, Some(to_table))
+            }
+            core::cmp::Ordering::Greater => {
+                let to_table = self.write_shard_ordered(to_index);
+                let from_table = self.write_shard_ordered(from_index);
+                (from_table, Some(to_table))
+            }
+        };
+
+        let Ok(entry) =
+            from_table.find_entry(from_hash, |(h, k, _)| *h == from_hash && from.equivalent(k))
+        else {
+            return false;
+        };
+        let ((_, _, value), _) = entry.remove();
+
+        let to_table = to_table.as_deref_mut().unwrap_or(&mut *from_table);
+        match to_table.entry(
+            to_hash,
+            |(h, k, _)| *h == to_hash && k == &to,
+            |(h, _, _)| *h,
+        ) {
+            Entry::Occupied(mut occ) => {
+                occ.get_mut().2 = value;
+                self.storage.shard_decrement(1);
+            }
+            Entry::Vacant(vac) => {
+                vac.insert((to_hash, to, value));
+            }
+        }
+        true
+    }
+
+    /// Get mutable references to the values of several distinct keys at once.
+    ///
+    /// The involved shards are write-locked in ascending index order, locking
+    /// each shard only once, so concurrent calls cannot deadlock each other.
+    /// All values can then be mutated together while the locks are held.
+    ///
+    /// Avoid calling other methods of this map that touch the locked shards
+    /// while the references are alive, as they would spin forever.
+    ///
+    /// # Arguments
+    /// * `keys` - The keys to look up, which must be distinct
+    ///
+    /// # Returns
+    /// A write reference for each key, or `None` if the key does not exist
+    ///
+    /// # Panics
+    /// Panics if the same key is passed more than once
+    pub fn get_disjoint_mut<const N: usize, Q>(
+        &self,
+        keys: [&Q; N],
+    ) -> [Option<LockedWriteRef<'_, K, V>>; N]
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        // Duplicate keys would alias mutable references, so check them in
+        // release builds too
+        assert!(
+            (0..N).all(|i| (i + 1..N).all(|j| keys[i] != keys[j])),
+            "Keys passed to get_disjoint_mut must be distinct"
+        );
+
+        let hashes = keys.map(|key| self.hash_key(key));
+        let indices = hashes.map(|hash| hash as usize & (self.storage.shards.len() - 1));
+        let mut order: [usize; N] = core::array::from_fn(|i| i);
+        order.sort_unstable_by_key(|&i| indices[i]);
+
+        let mut refs: [Option<LockedWriteRef<'_, K, V>>; N] = core::array::from_fn(|_| None);
+        let mut last_index = None;
+        for &i in order.iter() {
+            let index = indices[i];
+            if last_index == Some(index) {
+                continue;
+            }
+            last_index = Some(index);
+
+            let mut guard = self.write_shard_ordered(index);
+            let values = guard
+                .get_many_mut(hashes, |j, (h, k, _)| {
+                    indices[j] == index && *h == hashes[j] && keys[j].equivalent(k)
+                })
+                .map(|entry| entry.map(|(_, _, v)| NonNull::from(v)));
+            let guard = Rc::new(guard);
+
+            for (slot, value) in refs.iter_mut().zip(values) {
+                if let Some(value) = value {
+                    *slot = Some(LockedWriteRef {
+                        _guard: Rc::clone(&guard),
+                        value,
+                    });
+                }
+            }
+        }
+        refs
+    }
+
+    /// Run a batch of operations on the shard containing the key.
+    ///
+    /// The shard write lock is taken once and held while `f` runs, which
+    /// amortizes locking when updating several keys of the same shard. Keys
+    /// used in the session must belong to the same shard as `key`, which is
+    /// checked in debug builds.
+    ///
+    /// # Arguments
+    /// * `key` - A key selecting the shard to lock
+    /// * `f` - A closure that receives the session
+    ///
+    /// # Returns
+    /// The result of the closure
+    pub fn shard_session<Q, F, R>(&self, key: &Q, f: F) -> R
+    where
+        Q: ?Sized + Hash,
+        F: FnOnce(&mut ShardSession<'_, K, V, S>) -> R,
+    {
+        let shard = self.storage.shard_for_hash(self.hash_key(key));
+        let mut table = shard.table.write();
+        let mut session = ShardSession {
+            map: self,
+            shard,
+            table: &mut table,
+        };
+        f(&mut session)
+    }
+
+    /// Run a closure on the table of the shard at the given index.
+    ///
+    /// The shard write lock is held while `f` runs. Entries may be added or
+    /// removed through the table, the entry counter is adjusted afterwards.
+    /// Entries are stored as `(hash, key, value)`, and added entries must
+    /// hold the hash returned by [`hash_key`](ConcurrentMap::hash_key).
+    /// With debug assertions, every entry of the table is checked against
+    /// its key after `f` runs.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the shard, less than `shard_count()`
+    /// * `f` - A closure that receives the table of the shard
+    ///
+    /// # Returns
+    /// The result of the closure
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range
+    pub fn with_shard_by_index<F, R>(&self, index: usize, f: F) -> R
+    where
+        F: FnOnce(&mut HashTable<(u64, K, V)>) -> R,
+    {
+        assert!(index < self.shard_count(), "Shard index out of range");
+        let mut table = self.storage.shards[index].table.write();
+        let before = table.len();
+        let result = f(&mut table);
+        debug_assert!(
+            table.iter().all(|(h, k, _)| *h == self.hash_key(k)),
+            "Entry stored with a hash different from the hash of its key"
+        );
+        let after = table.len();
+        if after > before {
+            self.storage.shard_increment(after - before);
+        } else {
+            self.storage.shard_decrement(before - after);
+        }
+        result
+    }
+
+    /// Sample up to `n` keys from a single shard.
+    ///
+    /// Shards are picked round-robin across calls, skipping empty ones, so
+    /// only one shard is read-locked and scanned. The sample is approximate:
+    /// it is biased towards whatever order the shard table stores its keys.
+    ///
+    /// # Arguments
+    /// * `n` - The maximum number of keys to return
+    ///
+    /// # Returns
+    /// Clones of up to `n` keys, empty if the map is empty
+    pub fn sample_keys(&self, n: usize) -> Vec<K>
+    where
+        K: Clone,
+    {
+        let shards = &self.storage.shards;
+        let start = self.storage.sample_cursor.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..shards.len() {
+            let table = shards[start.wrapping_add(offset) & (shards.len() - 1)].table.read();
+            if !table.is_empty() {
+                return table.iter().take(n).map(|(_, k, _)| k.clone()).collect();
+            }
+        }
+        Vec::new()
+    }
+
+    /// Iterate the entries of the shards in `[start, end)`.
+    ///
+    /// Meant for workers that each own a contiguous range of shards: only
+    /// the shards of the range are locked, one at a time and only while it
+    /// is cloned. Entries are a snapshot per shard, not of the whole range.
+    ///
+    /// # Arguments
+    /// * `start` - The index of the first shard
+    /// * `end` - The index past the last shard, at most `shard_count()`
+    ///
+    /// # Returns
+    /// An iterator over clones of the entries of the range
+    ///
+    /// # Panics
+    /// Panics if `start > end` or `end > shard_count()`
+    pub fn iter_shard_range(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> impl Iterator<Item = (K, MaybeArc<V>)> + '_
+    where
+        K: Clone,
+        V: Clone,
+    {
+        assert!(
+            start <= end && end <= self.shard_count(),
+            "Shard range out of range"
+        );
+        self.storage
+            .shards
+            .iter()
+            .skip(start)
+            .take(end - start)
+            .flat_map(|shard| {
+                let table = shard.table.read();
+                table
+                    .iter()
+                    .map(|(_, k, v)| (k.clone(), MaybeArc::Owned(v.clone())))
+                    .collect::<Vec<_>>()
+            })
+    }
+
+    /// Iterate over owned clones of all entries.
+    ///
+    /// Built on [`iter_shard_range`](Self::iter_shard_range) over every
+    /// shard, so `map.entries_owned().collect::<HashMap<_, _>>()` moves the
+    /// contents into a standard map. Entries are a snapshot per shard.
+    ///
+    /// # Returns
+    /// An iterator over the entries, with values unwrapped from `MaybeArc`
+    pub fn entries_owned(&self) -> impl Iterator<Item = (K, V)> + '_
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.iter_shard_range(0, self.shard_count()).map(|(k, v)| (k, v.into_owned()))
+    }
+
+    /// Read the entry counter with relaxed ordering, for diagnostics.
+    ///
+    /// The value may be slightly stale, and is `None` when the map is in
+    /// [`CountingMode::Summed`]. See [`LockedStorage::raw_count`].
+    pub fn raw_count(&self) -> Option<usize> {
+        self.storage.raw_count()
+    }
+
+    /// Inspect how the entries are distributed across the shards.
+    ///
+    /// A large gap between `min` and `max` usually indicates a poor hasher,
+    /// which can be replaced at runtime with [`LockedMap::rehash_with`].
+    ///
+    /// # Returns
+    /// A summary of the per-shard entry counts
+    pub fn diagnose_distribution(&self) -> ShardDistribution {
+        let mut distribution = ShardDistribution {
+            min: usize::MAX,
+            max: 0,
+            empty_shards: 0,
+        };
+        for shard in self.storage.shards.iter() {
+            let len = shard.table.read().len();
+            distribution.min = distribution.min.min(len);
+            distribution.max = distribution.max.max(len);
+            if len == 0 {
+                distribution.empty_shards += 1;
+            }
+        }
+        distribution
+    }
+
+    /// Drain the map and rebuild its entries into a new map using another hasher.
+    ///
+    /// Shard assignment is fixed by the hash, so a bad hasher can only be fixed
+    /// by rebuilding. The new map keeps the same number of shards
+    /// and counting mode.
+    ///
+    /// # Arguments
+    /// * `new_hasher` - The hash builder used by the new map
+    ///
+    /// # Returns
+    /// A new map holding all entries drained from this one
+    pub fn rehash_with<S2>(&self, new_hasher: S2) -> LockedMap<K, V, S2>
+    where
+        S2: BuildHasher + Send + Sync,
+    {
+        self.rebuild(self.shard_count(), new_hasher, false)
+    }
+
+    /// Drain the map and rebuild its entries into a new map with another
+    /// number of shards.
+    ///
+    /// The new map keeps the same hasher and counting mode, so the hashes
+    /// stored with the entries are reused instead of hashing every key again.
+    ///
+    /// # Arguments
+    /// * `shards` - The number of shards of the new map (must be a power of two)
+    ///
+    /// # Returns
+    /// A new map holding all entries drained from this one
+    ///
+    /// # Panics
+    /// Panics if `shards` is not a power of two
+    pub fn reshard(&self, shards: usize) -> Self
+    where
+        S: Clone,
+    {
+        self.rebuild(shards, self.hasher().clone(), true)
+    }
+
+    /// Drain the map into a new map with the given shards and hasher.
+    ///
+    /// With `keep_hashes`, the stored hashes are reused, which is only valid
+    /// when the new hasher hashes keys like the current one.
+    fn rebuild<S2>(&self, shards: usize, new_hasher: S2, keep_hashes: bool) -> LockedMap<K, V, S2>
+    where
+        S2: BuildHasher + Send + Sync,
+    {
+        let storage = LockedStorage::with_shards_capacity_and_padding(
+            shards,
+            0,
+            self.storage.is_cache_padded(),
+        )
+        .with_counting_mode(self.storage.counting_mode());
+        let map = ConcurrentMap::with_storage_and_hasher(storage, new_hasher);
+        for shard in self.storage.shards.iter() {
+            let mut table = shard.table.write();
+            self.storage.shard_decrement(table.len());
+            for (h, k, v) in table.drain() {
+                let hash = if keep_hashes { h } else { map.hash_key(&k) };
+                // Drained keys are distinct, so they can be inserted unchecked
+                map.storage
+                    .shard_for_hash(hash)
+                    .table
+                    .write()
+                    .insert_unique(hash, (hash, k, v), |(h, _, _)| *h);
+                map.storage.shard_increment(1);
+            }
+        }
+        map
+    }
+
+    /// Replace the value of an existing key, without inserting a new key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to update
+    /// * `value` - The new value
+    ///
+    /// # Returns
+    /// `Ok` with the old value, or `Err` handing back `value` if the key
+    /// does not exist
+    pub fn swap_value<Q>(&self, key: &Q, value: V) -> Result<MaybeArc<V>, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let hash = self.hash_key(key);
+        let shard = self.storage.shard_for_hash(hash);
+        let mut table = shard.table.write();
+        match table.find_mut(hash, |(h, k, _)| *h == hash && key.equivalent(k)) {
+            Some((_, _, current)) => Ok(MaybeArc::Owned(core::mem::replace(current, value))),
+            None => Err(value),
+        }
+    }
+
+    /// Atomically replace the value of a key with one computed from it.
+    ///
+    /// The shard write lock is held while `f` runs, and the key is never
+    /// inserted or removed.
+    ///
+    /// # Arguments
+    /// * `key` - The key to update
+    /// * `f` - A closure computing the new value from the current one
+    ///
+    /// # Returns
+    /// The old and new values, or `None` if the key does not exist
+    pub fn replace_with<Q, F>(&self, key: &Q, f: F) -> Option<(MaybeArc<V>, MaybeArc<V>)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&V) -> V,
+        V: Clone,
+    {
+        self.alter(key, |value| {
+            let new = f(value);
+            let old = core::mem::replace(value, new.clone());
+            (MaybeArc::Owned(old), MaybeArc::Owned(new))
+        })
+    }
+
+    /// Insert the value if the key is absent, otherwise keep the existing value.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert if the key is absent
+    ///
+    /// # Returns
+    /// The value stored in the map after the call, either `value` or the
+    /// existing value
+    pub fn insert_or_get(&self, key: K, value: V) -> MaybeArc<V>
+    where
+        V: Clone,
+    {
+        self.get_or_compute_single_flight(key, || value)
+    }
+
+    /// Get the value of the key, or try to compute it and insert it if absent.
+    ///
+    /// Unlike [`get_or_compute_single_flight`](Self::get_or_compute_single_flight),
+    /// `f` runs without holding the shard lock, so concurrent callers may all
+    /// compute a value. The first one to be inserted wins, and the others are
+    /// discarded in favor of it.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A fallible closure that computes the value if the key is absent
+    ///
+    /// # Returns
+    /// The value stored in the map after the call, or the error of `f`, in
+    /// which case nothing is inserted
+    pub fn get_or_try_insert_with<F, E>(&self, key: K, f: F) -> Result<MaybeArc<V>, E>
+    where
+        V: Clone,
+        F: FnOnce() -> Result<V, E>,
+    {
+        if let Some(value) = self.view(&key, |_, v| v.clone()) {
+            return Ok(MaybeArc::Owned(value));
+        }
+        Ok(self.insert_or_get(key, f()?))
+    }
+
+    /// Insert the value if the key is absent, otherwise keep the existing value.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert if the key is absent
+    ///
+    /// # Returns
+    /// True if the key was added, false if it already existed
+    pub fn insert_if_absent(&self, key: K, value: V) -> bool {
+        let hash = self.hash_key(&key);
+        let shard = self.storage.shard_for_hash(hash);
+        let mut table = shard.table.write();
+
+        match table.entry(hash, |(h, k, _)| *h == hash && k == &key, |(h, _, _)| *h) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(vac) => {
+                vac.insert((hash, key, value));
+                self.storage.shard_increment(1);
+                true
+            }
+        }
+    }
+
+    /// Insert the value if the key is absent, otherwise modify the existing value.
+    ///
+    /// Unlike `alter_entry`, `on_existing` does not run on a newly inserted
+    /// value. Both paths happen under the shard write lock.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert or modify
+    /// * `value` - The value to insert if the key is absent
+    /// * `on_existing` - A closure modifying the existing value
+    ///
+    /// # Returns
+    /// True if `value` was inserted, false if an existing value was modified
+    pub fn upsert<F>(&self, key: K, value: V, on_existing: F) -> bool
+    where
+        F: FnOnce(&mut V),
+    {
+        let hash = self.hash_key(&key);
+        let shard = self.storage.shard_for_hash(hash);
+        let mut table = shard.table.write();
+
+        match table.entry(hash, |(h, k, _)| *h == hash && k == &key, |(h, _, _)| *h) {
+            Entry::Occupied(mut occ) => {
+                on_existing(&mut occ.get_mut().2);
+                false
+            }
+            Entry::Vacant(vac) => {
+                vac.insert((hash, key, value));
+                self.storage.shard_increment(1);
+                true
+            }
+        }
+    }
+
+    /// Extend the collection stored at the key, creating it if absent.
+    ///
+    /// The items are appended under the shard write lock, so concurrent
+    /// appends to the same key are never lost.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the collection
+    /// * `items` - The items to append
+    pub fn append_to_entry<E, I>(&self, key: K, items: I)
+    where
+        I: IntoIterator<Item = E>,
+        V: Extend<E> + Default,
+    {
+        self.alter_entry(key, V::default, |v| v.extend(items));
+    }
+
+    /// Add `by` to the counter of the key and report whether it crossed a threshold.
+    ///
+    /// Absent keys start from zero. The addition saturates, and runs under
+    /// the shard write lock, so among concurrent callers exactly one sees
+    /// the counter go from below `threshold` to at or above it.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the counter
+    /// * `by` - The amount to add
+    /// * `threshold` - The value whose crossing is reported
+    ///
+    /// # Returns
+    /// True only for the call that pushed the counter to `threshold` or beyond
+    pub fn increment_and_check(&self, key: K, by: V, threshold: V) -> bool
+    where
+        V: CounterValue,
+    {
+        let mut crossed = false;
+        self.alter_entry(key, V::default, |v| {
+            let old = *v;
+            *v = old.saturating_add(by);
+            crossed = old < threshold && *v >= threshold;
+        });
+        crossed
+    }
+
+    /// Get the value associated with the key, or compute and insert it if absent.
+    ///
+    /// The shard write lock is held while `f` runs, so concurrent callers for the
+    /// same key block until the value is inserted and then observe it instead of
+    /// computing it again.
+    ///
+    /// Since the whole shard is locked during the computation, `f` should be
+    /// reasonably quick and must not access this map.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that computes the value if the key is absent
+    ///
+    /// # Returns
+    /// The existing or newly computed value
+    pub fn get_or_compute_single_flight<F>(&self, key: K, f: F) -> MaybeArc<V>
+    where
+        V: Clone,
+        F: FnOnce() -> V,
+    {
+        let hash = self.hash_key(&key);
+        let shard = self.storage.shard_for_hash(hash);
+        let mut table = shard.table.write();
+
+        let entry = table.entry(
+            hash,
+            |(h, k_ref, _)| *h == hash && k_ref == &key,
+            |(h, _, _)| *h,
+        );
+
+        match entry {
+            Entry::Occupied(occ) => MaybeArc::Owned(occ.get().2.clone()),
+            Entry::Vacant(vac) => {
+                let value = f();
+                vac.insert((hash, key, value.clone()));
+                self.storage.shard_increment(1);
+                MaybeArc::Owned(value)
+            }
+        }
+    }
+}
+
+// Builder pattern support
+pub struct LockedMapBuilder<S = DefaultHashBuilder> {
+    shards: usize,
+    capacity: usize,
+    counting_mode: CountingMode,
+    cache_padding: bool,
+    hash_builder: Option<S>,
+}
+
+impl<S> Default for LockedMapBuilder<S>
+where
+    S: BuildHasher + Default + Send + Sync,
+ {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> LockedMapBuilder<S>
+where
+    S: BuildHasher + Default + Send + Sync,
+{
+    /// Create a new builder with default settings.
+    ///
+    /// # Returns
+    /// A new builder instance
+    pub fn new() -> Self {
+        Self {
+            shards: DEFAULT_SHARDS,
+            capacity: 0,
+            counting_mode: CountingMode::Atomic,
+            cache_padding: true,
+            hash_builder: None,
+        }
+    }
+    /// Set a custom hasher for the map.
     ///
     /// # Arguments
     /// * `hasher` - The hash builder to use
@@ -552,6 +1973,32 @@ where
         self
     }
 
+    /// Set how the map counts its entries.
+    ///
+    /// # Arguments
+    /// * `mode` - The counting mode to use
+    ///
+    /// # Returns
+    /// The builder instance for method chaining
+    pub fn with_counting_mode(mut self, mode: CountingMode) -> Self {
+        self.counting_mode = mode;
+        self
+    }
+
+    /// Set whether each shard is padded to its own cache line.
+    ///
+    /// Padding is enabled by default. See [`LockedStorage::with_cache_padding`].
+    ///
+    /// # Arguments
+    /// * `padded` - Whether to pad the shards
+    ///
+    /// # Returns
+    /// The builder instance for method chaining
+    pub fn with_cache_padding(mut self, padded: bool) -> Self {
+        self.cache_padding = padded;
+        self
+    }
+
     /// Build the LockedConcurrentMap with the specified parameters.
     ///
     /// # Returns
@@ -561,10 +2008,12 @@ where
         K: Hash + Eq + Send + Sync,
         V: Send + Sync,
     {
-        LockedMap::with_shards_and_capacity_and_hasher(
+        let storage = LockedStorage::with_shards_capacity_and_padding(
             self.shards,
             self.capacity,
-            self.hash_builder.unwrap_or_default(),
+            self.cache_padding,
         )
+        .with_counting_mode(self.counting_mode);
+        ConcurrentMap::with_storage_and_hasher(storage, self.hash_builder.unwrap_or_default())
     }
 }
@@ -2,25 +2,77 @@ use core::ops::Deref;
 
 use crate::hash::concurrent::traits::RawHashMap;
 
+mod adaptive_impl;
+mod arc_locked_impl;
+mod bounded_impl;
+mod comparator_impl;
+mod dynamic_impl;
+mod indexed_impl;
+mod lock_order;
 mod locked_impl;
+mod multi_impl;
+mod ordered_impl;
 mod rcu_impl;
+mod shards;
 mod traits;
+mod ttl_impl;
+mod versioned_impl;
 mod wrapper;
 
 #[cfg(test)]
 mod tests;
 
+pub mod adaptive {
+    pub use super::adaptive_impl::*;
+}
+
+pub mod arc_locked {
+    pub use super::arc_locked_impl::*;
+}
+
+pub mod bounded {
+    pub use super::bounded_impl::*;
+}
+
+pub mod comparator {
+    pub use super::comparator_impl::*;
+}
+
+pub mod dynamic {
+    pub use super::dynamic_impl::*;
+}
+
+pub mod indexed {
+    pub use super::indexed_impl::*;
+}
+
 pub mod locked {
     pub use super::locked_impl::*;
 }
 
+pub mod multi {
+    pub use super::multi_impl::*;
+}
+
+pub mod ordered {
+    pub use super::ordered_impl::*;
+}
+
 pub mod rcu {
     pub use super::rcu_impl::*;
 }
 
+pub mod ttl {
+    pub use super::ttl_impl::*;
+}
+
+pub mod versioned {
+    pub use super::versioned_impl::*;
+}
+
 pub mod prelude {
     pub use super::traits::*;
-    pub use super::wrapper::{MaybeArc, ConcurrentMap};
+    pub use super::wrapper::{MaybeArc, ConcurrentMap, MapStats};
 }
 
 pub type LockedMap<K, V> = DefaultHashMap<K, V, locked_impl::LockedMap<K, V>>;
@@ -0,0 +1,174 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::DefaultHashBuilder;
+
+use super::locked_impl::{LockedMap, DEFAULT_SHARDS};
+use super::traits::{MutableInPlaceMap, RawHashMap, ReadableInPlaceMap};
+use super::wrapper::MaybeArc;
+
+/// A locked concurrent map from each key to several values.
+///
+/// The values of a key are kept in insertion order, and may contain
+/// duplicates. A key only exists while it has at least one value: removing
+/// its last value removes the key as well.
+pub struct LockedMultiMap<K, V, S = DefaultHashBuilder> {
+    map: LockedMap<K, Vec<V>, S>,
+}
+
+impl<K, V> LockedMultiMap<K, V>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+    /// Create a new multimap with default settings.
+    ///
+    /// # Returns
+    /// A new, empty multimap
+    pub fn new() -> Self {
+        Self::with_hasher(Default::default())
+    }
+}
+
+impl<K, V> Default for LockedMultiMap<K, V>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> LockedMultiMap<K, V, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    /// Create a new multimap with a custom hash builder.
+    ///
+    /// # Arguments
+    /// * `hash_builder` - The hash builder to use
+    ///
+    /// # Returns
+    /// A new, empty multimap
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: LockedMap::with_shards_and_capacity_and_hasher(DEFAULT_SHARDS, 0, hash_builder),
+        }
+    }
+
+    /// Append a value to the values of a key, creating the key if absent.
+    ///
+    /// # Arguments
+    /// * `key` - The key to append to
+    /// * `value` - The value to append
+    pub fn insert(&self, key: K, value: V) {
+        self.map
+            .alter_entry(key, Vec::new, |values| values.push(value));
+    }
+
+    /// Get clones of all the values of a key, in insertion order.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    /// The values of the key, empty if the key does not exist
+    pub fn get_all<Q>(&self, key: &Q) -> Vec<MaybeArc<V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+    {
+        self.map
+            .view(key, |_, values| {
+                values.iter().cloned().map(MaybeArc::Owned).collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Remove the first occurrence of a value from the values of a key.
+    ///
+    /// The key is removed along with its last value.
+    ///
+    /// # Arguments
+    /// * `key` - The key to remove the value from
+    /// * `value` - The value to remove
+    ///
+    /// # Returns
+    /// True if the value was found and removed
+    pub fn remove_one<Q>(&self, key: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: PartialEq,
+    {
+        self.map.shard_session(key, |session| {
+            let Some(values) = session.get_mut(key) else {
+                return false;
+            };
+            let Some(index) = values.iter().position(|v| v == value) else {
+                return false;
+            };
+            values.remove(index);
+            if values.is_empty() {
+                session.remove(key);
+            }
+            true
+        })
+    }
+
+    /// Remove a key along with all its values.
+    ///
+    /// # Arguments
+    /// * `key` - The key to remove
+    ///
+    /// # Returns
+    /// The values that were removed, empty if the key did not exist
+    pub fn remove_all<Q>(&self, key: &Q) -> Vec<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map
+            .remove_entry(key)
+            .map_or_else(Vec::new, |(_, values)| values)
+    }
+
+    /// Get the number of values of a key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    /// The number of values, zero if the key does not exist
+    pub fn count<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.view(key, |_, values| values.len()).unwrap_or(0)
+    }
+
+    /// Check if the key has at least one value.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Get the number of keys in the multimap.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check if the multimap has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
@@ -0,0 +1,503 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::ptr::NonNull;
+
+use hashbrown::DefaultHashBuilder;
+use spin::Mutex;
+
+use crate::linked_list::intrusive::double::DoubleNode;
+use crate::linked_list::intrusive::list::LinkedList;
+use crate::linked_list::intrusive::traits::{List, NodeWithData};
+
+use super::locked_impl::{DEFAULT_SHARDS, LockedMap};
+use super::traits::{MutableInPlaceMap, RawHashMap, ReadableInPlaceMap};
+
+/// A value stored in the map along with its node in the order list.
+///
+/// The node is allocated by the order list, which owns it, so that its
+/// address stays stable while the entry is moved around by the table.
+struct OrderedEntry<K, V> {
+    value: V,
+    node: NonNull<DoubleNode<K>>,
+}
+
+// The node is only dereferenced under the lock of the order list.
+unsafe impl<K: Send, V: Send> Send for OrderedEntry<K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for OrderedEntry<K, V> {}
+
+/// The list threading all entries of a map in order.
+struct OrderList<K> {
+    list: LinkedList<DoubleNode<K>>,
+}
+
+impl<K> OrderList<K> {
+    /// Create a new empty list.
+    fn new() -> Self {
+        Self {
+            list: LinkedList::new(),
+        }
+    }
+
+    /// Allocate a node for the key and link it at the end of the list.
+    ///
+    /// # Returns
+    /// The node, owned by the list until it is [`release`](Self::release)d
+    fn push_back(&mut self, key: K) -> NonNull<DoubleNode<K>> {
+        let node = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(DoubleNode::new(key)))) };
+        self.list.push_back(node);
+        node
+    }
+
+    /// Allocate a node for the key and link it at the front of the list.
+    ///
+    /// # Returns
+    /// The node, owned by the list until it is [`release`](Self::release)d
+    fn push_front(&mut self, key: K) -> NonNull<DoubleNode<K>> {
+        let node = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(DoubleNode::new(key)))) };
+        unsafe { self.relink_front(node) };
+        node
+    }
+
+    /// Link a node at the front of the list.
+    ///
+    /// # Safety
+    ///
+    /// The node must be alive and not linked into any list.
+    unsafe fn relink_front(&mut self, node: NonNull<DoubleNode<K>>) {
+        self.list.push(node);
+    }
+
+    /// Unlink a node from the list.
+    ///
+    /// # Safety
+    ///
+    /// The node must be linked into this list.
+    unsafe fn unlink(&mut self, node: NonNull<DoubleNode<K>>) {
+        unsafe { self.list.quick_remove(node, None) };
+    }
+
+    /// Unlink a node from the list and free it.
+    ///
+    /// # Safety
+    ///
+    /// The node must have been allocated by this list and still be linked
+    /// into it, and must not be used afterwards.
+    unsafe fn release(&mut self, node: NonNull<DoubleNode<K>>) {
+        unsafe {
+            self.unlink(node);
+            drop(Box::from_raw(node.as_ptr()));
+        }
+    }
+
+    /// Unlink and free all nodes of the list.
+    fn clear(&mut self) {
+        while let Some(node) = self.list.pop() {
+            drop(unsafe { Box::from_raw(node.as_ptr()) });
+        }
+    }
+}
+
+impl<K> Drop for OrderList<K> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+// The nodes are owned by the map entries and only touched under the mutex.
+unsafe impl<K: Send> Send for OrderList<K> {}
+unsafe impl<K: Sync> Sync for OrderList<K> {}
+
+/// A locked concurrent map remembering the insertion order of its entries.
+///
+/// Every entry owns a node of an intrusive doubly linked list that threads
+/// all entries in the order they were first inserted. Replacing the value of
+/// an existing key keeps its position, removing a key unlinks its node.
+///
+/// Reads go straight to the sharded map, while inserts and removals are
+/// serialized by the lock of the order list.
+pub struct InsertionOrderedLockedMap<K, V, S = DefaultHashBuilder> {
+    map: LockedMap<K, OrderedEntry<K, V>, S>,
+    order: Mutex<OrderList<K>>,
+}
+
+impl<K, V> InsertionOrderedLockedMap<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+{
+    /// Create a new insertion ordered map with default settings.
+    ///
+    /// # Returns
+    /// A new insertion ordered map instance
+    pub fn new() -> Self {
+        Self::with_hasher(Default::default())
+    }
+}
+
+impl<K, V> Default for InsertionOrderedLockedMap<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> InsertionOrderedLockedMap<K, V, S>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    /// Create a new insertion ordered map with a custom hash builder.
+    ///
+    /// # Arguments
+    /// * `hash_builder` - The hash builder to use
+    ///
+    /// # Returns
+    /// A new insertion ordered map instance
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: LockedMap::with_shards_and_capacity_and_hasher(DEFAULT_SHARDS, 0, hash_builder),
+            order: Mutex::new(OrderList::new()),
+        }
+    }
+
+    /// Insert a key-value pair.
+    ///
+    /// A new key is appended to the end of the order, an existing key keeps
+    /// its position.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let mut order = self.order.lock();
+        if self.map.contains_key(&key) {
+            return self
+                .map
+                .alter(&key, |entry| core::mem::replace(&mut entry.value, value));
+        }
+
+        let node = order.push_back(key.clone());
+        self.map.insert(key, OrderedEntry { value, node });
+        None
+    }
+
+    /// Remove a key from the map and unlink it from the order.
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let mut order = self.order.lock();
+        self.map.remove_entry(key).map(|(_, entry)| {
+            unsafe { order.release(entry.node) };
+            entry.value
+        })
+    }
+
+    /// Remove all entries from the map and free their nodes.
+    pub fn clear(&self) {
+        let mut order = self.order.lock();
+        self.map.clear();
+        order.clear();
+    }
+
+    /// Get a clone of the value associated with the key.
+    ///
+    /// # Returns
+    /// A clone of the value, if the key exists
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+    {
+        self.view(key, |_, v| v.clone())
+    }
+
+    /// Run a closure on the entry associated with the key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the key and value references
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    pub fn view<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&K, &V) -> R,
+    {
+        self.map.view(key, |k, entry| f(k, &entry.value))
+    }
+
+    /// Check if a key exists in the map.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Get the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterate over a snapshot of the entries in insertion order.
+    ///
+    /// The snapshot is taken under the order lock, so inserts and removals
+    /// made during the iteration are not observed.
+    ///
+    /// # Returns
+    /// An iterator over clones of the entries, oldest first
+    pub fn iter(&self) -> alloc::vec::IntoIter<(K, V)>
+    where
+        V: Clone,
+    {
+        let order = self.order.lock();
+        let mut entries = Vec::with_capacity(order.list.count());
+        for node in unsafe { order.list.iter() } {
+            let key = unsafe { node.as_ref() }.data();
+            if let Some(value) = self.map.view(key, |_, entry| entry.value.clone()) {
+                entries.push((key.clone(), value));
+            }
+        }
+        entries.into_iter()
+    }
+}
+
+/// A locked concurrent map tracking how recently its entries were used.
+///
+/// Every entry owns a node of an intrusive doubly linked list ordering all
+/// entries from the most to the least recently used. Inserting a key and
+/// [`touch`](Self::touch)ing it move it to the front, while plain lookups
+/// leave the order alone. The least recently used entry, at the back, is
+/// the natural victim when building an LRU cache on top of the map.
+///
+/// Lookups go straight to the sharded map, while inserts, touches and
+/// removals are serialized by the lock of the recency list.
+pub struct RecencyLockedMap<K, V, S = DefaultHashBuilder> {
+    map: LockedMap<K, OrderedEntry<K, V>, S>,
+    recency: Mutex<OrderList<K>>,
+}
+
+impl<K, V> RecencyLockedMap<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+{
+    /// Create a new recency tracking map with default settings.
+    ///
+    /// # Returns
+    /// A new recency tracking map instance
+    pub fn new() -> Self {
+        Self::with_hasher(Default::default())
+    }
+}
+
+impl<K, V> Default for RecencyLockedMap<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> RecencyLockedMap<K, V, S>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    /// Create a new recency tracking map with a custom hash builder.
+    ///
+    /// # Arguments
+    /// * `hash_builder` - The hash builder to use
+    ///
+    /// # Returns
+    /// A new recency tracking map instance
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: LockedMap::with_shards_and_capacity_and_hasher(DEFAULT_SHARDS, 0, hash_builder),
+            recency: Mutex::new(OrderList::new()),
+        }
+    }
+
+    /// Insert a key-value pair and mark the key as the most recently used.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let mut recency = self.recency.lock();
+        if self.map.contains_key(&key) {
+            return self.map.alter(&key, |entry| {
+                unsafe {
+                    recency.unlink(entry.node);
+                    recency.relink_front(entry.node);
+                }
+                core::mem::replace(&mut entry.value, value)
+            });
+        }
+
+        let node = recency.push_front(key.clone());
+        self.map.insert(key, OrderedEntry { value, node });
+        None
+    }
+
+    /// Mark the key as the most recently used.
+    ///
+    /// The entry is looked up under its shard write lock, and its node is
+    /// moved to the front of the recency list.
+    ///
+    /// # Arguments
+    /// * `key` - The key to touch
+    ///
+    /// # Returns
+    /// True if the key exists, false otherwise
+    pub fn touch<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let mut recency = self.recency.lock();
+        self.map
+            .alter(key, |entry| unsafe {
+                recency.unlink(entry.node);
+                recency.relink_front(entry.node);
+            })
+            .is_some()
+    }
+
+    /// Get a clone of the least recently used key.
+    ///
+    /// # Returns
+    /// The key at the back of the recency list, if the map is not empty
+    pub fn lru_key(&self) -> Option<K> {
+        let recency = self.recency.lock();
+        recency
+            .list
+            .tail()
+            .map(|node| unsafe { node.as_ref() }.data().clone())
+    }
+
+    /// Remove the least recently used entry.
+    ///
+    /// # Returns
+    /// The key and value that were removed, if the map was not empty
+    pub fn pop_lru(&self) -> Option<(K, V)> {
+        let mut recency = self.recency.lock();
+        let tail = recency.list.tail()?;
+        let key = unsafe { tail.as_ref() }.data();
+        let (key, entry) = self.map.remove_entry(key)?;
+        unsafe { recency.release(entry.node) };
+        Some((key, entry.value))
+    }
+
+    /// Remove a key from the map and unlink it from the recency list.
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let mut recency = self.recency.lock();
+        self.map.remove_entry(key).map(|(_, entry)| {
+            unsafe { recency.release(entry.node) };
+            entry.value
+        })
+    }
+
+    /// Remove all entries from the map and free their nodes.
+    pub fn clear(&self) {
+        let mut recency = self.recency.lock();
+        self.map.clear();
+        recency.clear();
+    }
+
+    /// Get a clone of the value associated with the key, without touching it.
+    ///
+    /// # Returns
+    /// A clone of the value, if the key exists
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+    {
+        self.view(key, |_, v| v.clone())
+    }
+
+    /// Run a closure on the entry associated with the key, without touching it.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the key and value references
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    pub fn view<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&K, &V) -> R,
+    {
+        self.map.view(key, |k, entry| f(k, &entry.value))
+    }
+
+    /// Check if a key exists in the map, without touching it.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Get the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Get a snapshot of the keys from the most to the least recently used.
+    ///
+    /// # Returns
+    /// Clones of all keys, most recently used first
+    pub fn keys_by_recency(&self) -> Vec<K> {
+        let recency = self.recency.lock();
+        unsafe { recency.list.iter() }
+            .map(|node| unsafe { node.as_ref() }.data().clone())
+            .collect()
+    }
+}
@@ -1,4 +1,4 @@
-use alloc::boxed::Box;
+use alloc::borrow::Cow;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::borrow::Borrow;
@@ -7,13 +7,21 @@ use core::ops::{Deref, DerefMut};
 use core::sync::atomic::{AtomicUsize, Ordering};
 
 use arc_swap::ArcSwap;
-use crossbeam_utils::CachePadded;
-use hashbrown::DefaultHashBuilder;
+use hashbrown::{DefaultHashBuilder, HashSet};
+// Transactions spanning shards are serialized by a lock, spinning by
+// default like the locked map, and parking with the `parking_lot` feature.
+#[cfg(feature = "parking_lot")]
+use parking_lot::Mutex;
 use rpds::{HashTrieMap, HashTrieMapSync};
+#[cfg(not(feature = "parking_lot"))]
+use spin::Mutex;
 
 use crate::hash::concurrent::wrapper::MaybeArc;
+use crate::hash::fixed::FixedMap;
+use crate::hash::seeded::SeededHashBuilder;
 
-use super::traits::{RawHashMap, ReadableMap, ShardStorage, MutableMap, AtomicSet, MutableGuard, MutableInPlaceMap, ReadableInPlaceMap};
+use super::shards::Shards;
+use super::traits::{RawHashMap, ReadableMap, ShardStorage, MutableMap, AtomicSet, MutableGuard, MutableInPlaceMap, ReadableInPlaceMap, CounterValue};
 use super::wrapper::ConcurrentMap;
 
 /// A simple backoff strategy for spin-then-yield.
@@ -85,6 +93,10 @@ where
             Err(())
         }
     }
+
+    fn into_inner(self) -> V {
+        self.value
+    }
 }
 
 /// A single shard of the RCU hash table.
@@ -110,9 +122,11 @@ where
 /// This storage uses ArcSwap to provide lock-free reads
 /// and efficient copy-on-write updates, without a separate GC mechanism.
 pub struct RcuStorage<K, V> {
-    shards: Box<[CachePadded<RcuShard<K, V>>]>,
+    shards: Shards<RcuShard<K, V>>,
     /// Atomic counter for the number of objects in the storage
     count: AtomicUsize,
+    /// Serializes transactions, which publish one shard at a time
+    transactions: Mutex<()>,
 }
 
 // RcuStorage no longer needs a custom Drop impl, as ArcSwap handles everything.
@@ -132,19 +146,103 @@ where
     /// # Panics
     /// Panics if `shards` is not a power of two
     pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_padding(shards, true)
+    }
+
+    /// Create new RCU storage with the given shard layout.
+    ///
+    /// The shards are laid out padded or packed right away, see
+    /// [`with_cache_padding`](Self::with_cache_padding). A single shard has
+    /// no neighbour to share its cache line with, so it is never padded.
+    ///
+    /// # Arguments
+    /// * `shards` - The number of shards (must be a power of two)
+    /// * `padded` - Whether to pad each shard to its own cache line
+    ///
+    /// # Returns
+    /// A new RCU storage instance
+    ///
+    /// # Panics
+    /// Panics if `shards` is not a power of two
+    pub fn with_shards_and_padding(shards: usize, padded: bool) -> Self {
         assert!(
             shards.is_power_of_two(),
             "Number of shards must be a power of two"
         );
-        let mut shard_vec = Vec::with_capacity(shards);
-        for _ in 0..shards {
-            shard_vec.push(CachePadded::new(RcuShard::default()));
-        }
         Self {
-            shards: shard_vec.into_boxed_slice(),
+            shards: Shards::new(
+                (0..shards).map(|_| RcuShard::default()),
+                padded && shards > 1,
+            ),
             count: AtomicUsize::new(0),
+            transactions: Mutex::new(()),
+        }
+    }
+}
+
+impl<K, V> RcuStorage<K, V> {
+    /// Create a new storage whose shards start from the current shard tables.
+    ///
+    /// The persistent tables are shared structurally, so the fork is cheap and
+    /// both storages diverge copy-on-write as they are mutated.
+    ///
+    /// # Returns
+    /// A new RCU storage instance holding the same entries
+    pub fn fork(&self) -> Self {
+        let tables: Vec<_> = self
+            .shards
+            .iter()
+            .map(|shard| shard.table.load_full())
+            .collect();
+        // Count the snapshots themselves, the shared counter may already
+        // include writes published after them
+        let count = tables.iter().map(|table| table.size()).sum();
+        let shards = tables.into_iter().map(|table| RcuShard {
+            table: ArcSwap::new(table),
+        });
+        Self {
+            shards: Shards::new(shards, self.is_cache_padded()),
+            count: AtomicUsize::new(count),
+            transactions: Mutex::new(()),
         }
     }
+
+    /// Set whether each shard is padded to its own cache line.
+    ///
+    /// Shards are padded by default, so that threads working on neighbouring
+    /// shards do not contend on the same cache line. Without padding the
+    /// shards are packed, which saves memory on constrained targets with
+    /// many shards at the cost of some false sharing.
+    ///
+    /// # Arguments
+    /// * `padded` - Whether to pad the shards
+    ///
+    /// # Returns
+    /// The storage instance for method chaining
+    pub fn with_cache_padding(mut self, padded: bool) -> Self {
+        self.shards = self.shards.with_cache_padding(padded);
+        self
+    }
+
+    /// Check if each shard is padded to its own cache line.
+    pub fn is_cache_padded(&self) -> bool {
+        self.shards.is_padded()
+    }
+
+    /// Get the number of bytes taken by the shard slice, padding included.
+    ///
+    /// The tables owned by the shards are not included.
+    pub fn shards_size_in_bytes(&self) -> usize {
+        self.shards.size_in_bytes()
+    }
+
+    /// Read the entry counter with relaxed ordering.
+    ///
+    /// This is a cheap diagnostic read that may be slightly stale, unlike
+    /// `len()` which reads the counter with acquire ordering.
+    pub fn raw_count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
 }
 
 // Default number of shards. Must be a power of two.
@@ -157,7 +255,7 @@ where
 {
     type Shard = RcuShard<K, V>;
 
-    fn shard_for_hash(&self, hash: u64) -> &CachePadded<Self::Shard> {
+    fn shard_for_hash(&self, hash: u64) -> &Self::Shard {
         &self.shards[hash as usize & (self.shards.len() - 1)]
     }
 
@@ -200,6 +298,26 @@ where
     }
 }
 
+impl<K, V> HamtMap<K, V, SeededHashBuilder>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+    /// Create a new RCU concurrent map hashing with a fixed seed.
+    ///
+    /// Maps created with the same seed place the same keys in the same
+    /// shards and iterate them in the same order on every run.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed of the hash builder
+    ///
+    /// # Returns
+    /// A new RCU concurrent map instance
+    pub fn with_seeded_hasher(seed: u64) -> Self {
+        Self::with_shards_and_hasher(DEFAULT_SHARDS, SeededHashBuilder::with_seed(seed))
+    }
+}
+
 impl<K, V, S> HamtMap<K, V, S>
 where
     K: Hash + Eq + Send + Sync,
@@ -221,6 +339,35 @@ where
         let storage = RcuStorage::with_shards(shards);
         ConcurrentMap::with_storage_and_hasher(storage, hash_builder)
     }
+
+    /// Read the entry counter with relaxed ordering, for diagnostics.
+    ///
+    /// The value may be slightly stale. See [`RcuStorage::raw_count`].
+    pub fn raw_count(&self) -> usize {
+        self.storage.raw_count()
+    }
+}
+
+impl<K, V, S> HamtMap<K, V, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Clone + Send + Sync,
+{
+    /// Fork the map into a new one sharing the current contents.
+    ///
+    /// Each shard of the fork starts from the same persistent table as the
+    /// original, so no entries are copied. Subsequent writes to either map
+    /// are not visible to the other.
+    ///
+    /// The fork is taken shard by shard, so writes racing with it may be
+    /// visible in some shards of the fork but not in others.
+    ///
+    /// # Returns
+    /// A new RCU concurrent map with the same entries and hasher
+    pub fn fork(&self) -> Self {
+        ConcurrentMap::with_storage_and_hasher(self.storage.fork(), self.hash_builder.clone())
+    }
 }
 
 impl<K, V, S> Default for HamtMap<K, V, S>
@@ -471,21 +618,1031 @@ where
             .unwrap_or(None)
     }
 
+    fn try_alter<Q, F, R, E>(&self, key: &Q, f: F) -> Option<Result<R, E>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&mut V) -> Result<R, E>,
+    {
+        let mut guard = self.get_mut(key)?;
+        match f(&mut guard) {
+            // The working copy is dropped without being published
+            Err(err) => Some(Err(err)),
+            Ok(ret) => guard.commit().ok().map(|_| Ok(ret)),
+        }
+    }
+
     fn alter_entry<F, D>(&self, key: K, default: D, f: F)
     where
         F: FnOnce(&mut V),
         D: FnOnce() -> V,
     {
-        self.get_mut(&key)
-            .map(|mut guard| {
+        match self.get_mut(&key) {
+            Some(mut guard) => {
                 f(&mut guard);
-                guard.commit().is_ok()
+                let _ = guard.commit();
+            }
+            None => {
+                // If the key was not found, insert a new entry with the modified default value
+                let mut value = default();
+                f(&mut value);
+                self.insert(key, value);
+            }
+        }
+    }
+
+    fn update_entry<F, D>(&self, key: K, mut default: D, mut f: F)
+    where
+        F: FnMut(&mut V),
+        D: FnMut() -> V,
+    {
+        let shard = self.shard_for_key(&key);
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            let existing = old_arc.get(&key);
+            let inserted = existing.is_none();
+            let mut value = existing.map_or_else(&mut default, |v| V::clone(v));
+            f(&mut value);
+            let new_arc = Arc::new(old_arc.insert(key.clone(), Arc::new(value)));
+
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                if inserted {
+                    self.storage.shard_increment(1);
+                }
+                return;
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+}
+
+impl<K, V, S> HamtMap<K, V, S>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    /// Remove and return the entire entry associated with the key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to remove
+    ///
+    /// # Returns
+    /// The key-value pair that was removed, cloned out of the table, if the
+    /// key existed
+    pub fn remove_entry<Q>(&self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+    {
+        let shard = self.shard_for_key(key);
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            let (k, v) = old_arc.get_key_value(key)?;
+            let entry = (k.clone(), v.as_ref().clone());
+            let new_arc = Arc::new(old_arc.remove(key));
+
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                self.storage.shard_decrement(1);
+                return Some(entry);
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Remove a key and take ownership of its value if it is not shared.
+    ///
+    /// Values read from the map share their `Arc` with the table, so the
+    /// value can only be unwrapped when no reader still holds it.
+    ///
+    /// # Arguments
+    /// * `key` - The key to remove
+    ///
+    /// # Returns
+    /// `Ok` with the value if this was its last reference, `Err` with the
+    /// shared value otherwise, or `None` if the key does not exist
+    pub fn remove_owned<Q>(&self, key: &Q) -> Option<Result<V, Arc<V>>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.remove(key)
+            .and_then(MaybeArc::try_shared)
+            .map(Arc::try_unwrap)
+    }
+
+    /// Get the value associated with the key as a [`Cow`].
+    ///
+    /// Values are shared with the persistent table, which may be replaced
+    /// at any time, so they cannot be borrowed past the load of the table.
+    /// The value is cloned out of its shared `Arc` and returned as
+    /// `Cow::Owned`. See [`LockedMap::get_cow`] for the borrowing variant.
+    ///
+    /// [`LockedMap::get_cow`]: super::locked_impl::LockedMap::get_cow
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    /// An owned copy of the value, if the key exists
+    pub fn get_cow<Q>(&self, key: &Q) -> Option<Cow<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+    {
+        self.get(key).map(|value| Cow::Owned(value.into_owned()))
+    }
+
+    /// Move the value of a key to another key, replacing any value there.
+    ///
+    /// When both keys belong to the same shard, the move is a single table
+    /// swap, so readers see the value under exactly one of the keys. Across
+    /// shards the value is removed first and inserted afterwards, so readers
+    /// may briefly see it under neither key.
+    ///
+    /// # Arguments
+    /// * `from` - The key to move the value from
+    /// * `to` - The key to move the value to
+    ///
+    /// # Returns
+    /// True if the value was moved, false if `from` does not exist
+    pub fn rename<Q>(&self, from: &Q, to: K) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let from_shard = self.shard_for_key(from);
+        let to_shard = self.shard_for_key(&to);
+
+        let mut backoff_step = 0;
+        if core::ptr::eq(from_shard, to_shard) {
+            loop {
+                let old_arc = from_shard.table.load();
+                let Some(value) = old_arc.get(from).cloned() else {
+                    return false;
+                };
+                let removed = old_arc.remove(from);
+                let replaced = removed.contains_key(&to);
+                let new_arc = Arc::new(removed.insert(to.clone(), value));
+
+                if Arc::ptr_eq(&old_arc, &from_shard.table.compare_and_swap(&old_arc, new_arc)) {
+                    if replaced {
+                        self.storage.shard_decrement(1);
+                    }
+                    return true;
+                }
+                backoff(&mut backoff_step);
+            }
+        }
+
+        let Some(value) = self.remove(from).and_then(MaybeArc::try_shared) else {
+            return false;
+        };
+        loop {
+            let old_arc = to_shard.table.load();
+            let replaced = old_arc.contains_key(&to);
+            let new_arc = Arc::new(old_arc.insert(to.clone(), Arc::clone(&value)));
+
+            if Arc::ptr_eq(&old_arc, &to_shard.table.compare_and_swap(&old_arc, new_arc)) {
+                if !replaced {
+                    self.storage.shard_increment(1);
+                }
+                return true;
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Check if all the keys exist in the map.
+    ///
+    /// The keys are grouped by shard, so each shard table is loaded at most
+    /// once. Each shard is checked on its own snapshot, so the result is not
+    /// a snapshot across shards.
+    ///
+    /// # Arguments
+    /// * `keys` - The keys to check for
+    ///
+    /// # Returns
+    /// True if every key exists, false as soon as one is missing
+    pub fn contains_all<Q>(&self, keys: &[&Q]) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.keys_by_shard(keys)
+            .chunk_by(|a, b| a.0 == b.0)
+            .all(|group| {
+                let table = self.storage.shards[group[0].0].table.load();
+                group.iter().all(|(_, _, key)| table.contains_key(*key))
+            })
+    }
+
+    /// Check if any of the keys exists in the map.
+    ///
+    /// The keys are grouped by shard, so each shard table is loaded at most
+    /// once.
+    ///
+    /// # Arguments
+    /// * `keys` - The keys to check for
+    ///
+    /// # Returns
+    /// True as soon as one key exists, false if none does
+    pub fn contains_any<Q>(&self, keys: &[&Q]) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.keys_by_shard(keys)
+            .chunk_by(|a, b| a.0 == b.0)
+            .any(|group| {
+                let table = self.storage.shards[group[0].0].table.load();
+                group.iter().any(|(_, _, key)| table.contains_key(*key))
             })
-            .unwrap_or_else(|| {
-                // If the key was not found, insert a new entry with the default value
-                let value = default();
-                self.insert(key, value).is_none()
-            });
+    }
+
+    /// Get a value along with the number of entries in its shard.
+    ///
+    /// Both are read from the same shard snapshot, so the length is
+    /// consistent with the lookup. Useful for per-shard load decisions
+    /// without a second lookup.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    /// The value and the length of its shard, if the key exists
+    pub fn get_with_shard_len<Q>(&self, key: &Q) -> Option<(MaybeArc<V>, usize)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let shard = self.shard_for_key(key);
+        let table_arc = shard.table.load_full();
+        table_arc
+            .get(key)
+            .map(|v| (MaybeArc::Shared(Arc::clone(v)), table_arc.size()))
+    }
+
+    /// Insert clones of every entry of a [`FixedMap`].
+    ///
+    /// The entries are grouped by shard, and each group is swapped into its
+    /// shard with a single CAS, retried if another thread modified the shard
+    /// meanwhile. Existing keys take the value of the fixed map.
+    ///
+    /// # Arguments
+    /// * `fixed` - The map to copy the entries from
+    pub fn apply_fixed<const CAP: usize, S2>(&self, fixed: &FixedMap<K, V, CAP, S2>)
+    where
+        V: Clone,
+        S2: BuildHasher,
+    {
+        for group in self.entries_by_shard(fixed).chunk_by(|a, b| a.0 == b.0) {
+            let shard = &self.storage.shards[group[0].0];
+            let mut backoff_step = 0;
+            loop {
+                let old_arc = shard.table.load();
+                let mut new_table = old_arc.as_ref().clone();
+                let mut inserted = 0;
+                for &(_, _, key, value) in group {
+                    if !new_table.contains_key(key) {
+                        inserted += 1;
+                    }
+                    new_table.insert_mut(key.clone(), Arc::new(value.clone()));
+                }
+
+                if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, Arc::new(new_table))) {
+                    self.storage.shard_increment(inserted);
+                    break;
+                }
+                backoff(&mut backoff_step);
+            }
+        }
+    }
+
+    /// Insert the value if the key is absent, otherwise keep the existing value.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert if the key is absent
+    ///
+    /// # Returns
+    /// The value stored in the map after the call, either `value` or the
+    /// existing value
+    pub fn insert_or_get(&self, key: K, value: V) -> MaybeArc<V> {
+        let shard = self.shard_for_key(&key);
+        let value = Arc::new(value);
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            if let Some(existing) = old_arc.get(&key) {
+                return MaybeArc::Shared(Arc::clone(existing));
+            }
+
+            let new_arc = Arc::new(old_arc.insert(key.clone(), Arc::clone(&value)));
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                self.storage.shard_increment(1);
+                return MaybeArc::Shared(value);
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Get the value of the key, or try to compute it and insert it if absent.
+    ///
+    /// Concurrent callers may all compute a value. The first one to be
+    /// inserted wins, and the others are discarded in favor of it.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A fallible closure that computes the value if the key is absent
+    ///
+    /// # Returns
+    /// The value stored in the map after the call, or the error of `f`, in
+    /// which case nothing is inserted
+    pub fn get_or_try_insert_with<F, E>(&self, key: K, f: F) -> Result<MaybeArc<V>, E>
+    where
+        F: FnOnce() -> Result<V, E>,
+    {
+        if let Some(existing) = self.shard_for_key(&key).table.load().get(&key) {
+            return Ok(MaybeArc::Shared(Arc::clone(existing)));
+        }
+        Ok(self.insert_or_get(key, f()?))
+    }
+
+    /// Get the shared value of the key, initializing it if absent.
+    ///
+    /// Only the value swapped in by the winning CAS is ever stored, and
+    /// every caller gets that same `Arc`, so the key maps to a single
+    /// instance. `init` runs at most once per call, but concurrent callers
+    /// that lose the race still run it and their values are dropped.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `init` - A closure that computes the value if the key is absent
+    ///
+    /// # Returns
+    /// The value stored in the map after the call
+    pub fn get_or_init_arc<F>(&self, key: K, init: F) -> Arc<V>
+    where
+        F: FnOnce() -> V,
+    {
+        let shard = self.shard_for_key(&key);
+        let mut init = Some(init);
+        let mut value: Option<Arc<V>> = None;
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            if let Some(existing) = old_arc.get(&key) {
+                return Arc::clone(existing);
+            }
+
+            let value = value.get_or_insert_with(|| Arc::new(init.take().unwrap()()));
+            let new_arc = Arc::new(old_arc.insert(key.clone(), Arc::clone(value)));
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                self.storage.shard_increment(1);
+                return Arc::clone(value);
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Insert the value if the key is absent, otherwise keep the existing value.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert if the key is absent
+    ///
+    /// # Returns
+    /// True if the key was added, false if it already existed
+    pub fn insert_if_absent(&self, key: K, value: V) -> bool {
+        let shard = self.shard_for_key(&key);
+        let value = Arc::new(value);
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            if old_arc.contains_key(&key) {
+                return false;
+            }
+
+            let new_arc = Arc::new(old_arc.insert(key.clone(), Arc::clone(&value)));
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                self.storage.shard_increment(1);
+                return true;
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Insert the value if the key is absent, otherwise modify the existing value.
+    ///
+    /// Unlike `alter_entry`, `on_existing` does not run on a newly inserted
+    /// value. The existing value is cloned, modified and swapped in with a
+    /// single CAS, and the whole step is retried if another thread modified
+    /// the shard meanwhile, so `on_existing` may run several times.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert or modify
+    /// * `value` - The value to insert if the key is absent
+    /// * `on_existing` - A closure modifying a clone of the existing value
+    ///
+    /// # Returns
+    /// True if `value` was inserted, false if an existing value was modified
+    pub fn upsert<F>(&self, key: K, value: V, mut on_existing: F) -> bool
+    where
+        F: FnMut(&mut V),
+        V: Clone,
+    {
+        let shard = self.shard_for_key(&key);
+        let value = Arc::new(value);
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            let (new_value, inserted) = match old_arc.get(&key) {
+                Some(current) => {
+                    let mut modified = current.as_ref().clone();
+                    on_existing(&mut modified);
+                    (Arc::new(modified), false)
+                }
+                None => (Arc::clone(&value), true),
+            };
+            let new_arc = Arc::new(old_arc.insert(key.clone(), new_value));
+
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                if inserted {
+                    self.storage.shard_increment(1);
+                }
+                return inserted;
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Extend the collection stored at the key, creating it if absent.
+    ///
+    /// The items are collected once, then the existing collection, or a
+    /// default one, is cloned, extended and swapped in with a single CAS.
+    /// The whole step is retried if another thread modified the shard
+    /// meanwhile, so concurrent appends to the same key are never lost.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the collection
+    /// * `items` - The items to append
+    pub fn append_to_entry<E, I>(&self, key: K, items: I)
+    where
+        I: IntoIterator<Item = E>,
+        E: Clone,
+        V: Extend<E> + Default + Clone,
+    {
+        let shard = self.shard_for_key(&key);
+        let items = items.into_iter().collect::<Vec<_>>();
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            let current = old_arc.get(&key);
+            let inserted = current.is_none();
+            let mut value = current.map_or_else(V::default, |v| v.as_ref().clone());
+            value.extend(items.iter().cloned());
+            let new_arc = Arc::new(old_arc.insert(key.clone(), Arc::new(value)));
+
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                if inserted {
+                    self.storage.shard_increment(1);
+                }
+                return;
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Add `by` to the counter of the key and report whether it crossed a threshold.
+    ///
+    /// Absent keys start from zero. The addition saturates, and is swapped
+    /// in with a single CAS retried on contention, so among concurrent
+    /// callers exactly one sees the counter go from below `threshold` to at
+    /// or above it.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the counter
+    /// * `by` - The amount to add
+    /// * `threshold` - The value whose crossing is reported
+    ///
+    /// # Returns
+    /// True only for the call that pushed the counter to `threshold` or beyond
+    pub fn increment_and_check(&self, key: K, by: V, threshold: V) -> bool
+    where
+        V: CounterValue,
+    {
+        let shard = self.shard_for_key(&key);
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            let old = old_arc.get(&key).map(|v| **v);
+            let new = old.unwrap_or_default().saturating_add(by);
+            let new_arc = Arc::new(old_arc.insert(key.clone(), Arc::new(new)));
+
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                if old.is_none() {
+                    self.storage.shard_increment(1);
+                }
+                return old.unwrap_or_default() < threshold && new >= threshold;
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Atomically update the value of a key with a closure.
+    ///
+    /// Like `AtomicUsize::fetch_update`, the current value is read and passed
+    /// to `f`. If `f` returns `Some(new)`, the new value is swapped in, and the
+    /// whole step is retried if another thread modified the shard meanwhile,
+    /// so `f` may run several times.
+    ///
+    /// # Arguments
+    /// * `key` - The key to update
+    /// * `f` - A closure computing the new value from the current one
+    ///
+    /// # Returns
+    /// `Ok` with the stored new value, or `Err` if the key does not exist or
+    /// `f` returned `None`
+    #[allow(clippy::result_unit_err)]
+    pub fn fetch_update<Q, F>(&self, key: &Q, mut f: F) -> Result<MaybeArc<V>, ()>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnMut(&V) -> Option<V>,
+    {
+        let shard = self.shard_for_key(key);
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            let (k, current) = old_arc.get_key_value(key).ok_or(())?;
+            let new_value = Arc::new(f(current).ok_or(())?);
+            let new_arc = Arc::new(old_arc.insert(k.clone(), Arc::clone(&new_value)));
+
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                return Ok(MaybeArc::Shared(new_value));
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Replace the value of an existing key, without inserting a new key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to update
+    /// * `value` - The new value
+    ///
+    /// # Returns
+    /// `Ok` with the old value, or `Err` handing back `value` if the key
+    /// does not exist
+    pub fn swap_value<Q>(&self, key: &Q, value: V) -> Result<MaybeArc<V>, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let shard = self.shard_for_key(key);
+        let value = Arc::new(value);
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            let Some((k, current)) = old_arc.get_key_value(key) else {
+                // Tables of failed swaps are dropped, so the value is unshared
+                return Err(Arc::into_inner(value).expect("Value must not be shared"));
+            };
+            let new_arc = Arc::new(old_arc.insert(k.clone(), Arc::clone(&value)));
+
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                return Ok(MaybeArc::Shared(Arc::clone(current)));
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Atomically replace the value of a key with one computed from it.
+    ///
+    /// Unlike [`HamtMap::fetch_update`], the update cannot be aborted, and
+    /// the key is never inserted or removed. The step is retried if another
+    /// thread modified the shard meanwhile, so `f` may run several times.
+    ///
+    /// # Arguments
+    /// * `key` - The key to update
+    /// * `f` - A closure computing the new value from the current one
+    ///
+    /// # Returns
+    /// The old and new values, or `None` if the key does not exist
+    pub fn replace_with<Q, F>(&self, key: &Q, mut f: F) -> Option<(MaybeArc<V>, MaybeArc<V>)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnMut(&V) -> V,
+    {
+        let shard = self.shard_for_key(key);
+
+        let mut backoff_step = 0;
+        loop {
+            let old_arc = shard.table.load();
+            let (k, current) = old_arc.get_key_value(key)?;
+            let new_value = Arc::new(f(current));
+            let new_arc = Arc::new(old_arc.insert(k.clone(), Arc::clone(&new_value)));
+
+            if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, new_arc)) {
+                return Some((
+                    MaybeArc::Shared(Arc::clone(current)),
+                    MaybeArc::Shared(new_value),
+                ));
+            }
+            backoff(&mut backoff_step);
+        }
+    }
+
+    /// Update the values of several distinct keys together.
+    ///
+    /// The involved shard tables are loaded once each, clones of the values
+    /// are passed to `f`, and the results are published with one CAS per
+    /// shard, in ascending shard order. If one of the keys is modified
+    /// before the first shard is published, nothing was published and the
+    /// whole transaction is retried, so `f` may run several times. A CAS
+    /// failing because of writes to other keys of the shard is retried over
+    /// the current table, which never loses those writes.
+    ///
+    /// Transactions are serialized by a lock of the map, so they never lose
+    /// each other's updates and, for example, transfers between keys
+    /// conserve their total. When all keys belong to the same shard, the
+    /// update is published atomically. Across shards it is not: readers may
+    /// see the first shards updated before the last ones, and a plain write
+    /// to one of the keys, racing with the publication of a later shard, is
+    /// overwritten by the transaction.
+    ///
+    /// # Arguments
+    /// * `keys` - The keys to update, which must be distinct
+    /// * `f` - A closure updating clones of the values in place
+    ///
+    /// # Returns
+    /// True if the values were updated, false if a key does not exist
+    pub fn transact<const N: usize, F>(&self, keys: [K; N], mut f: F) -> bool
+    where
+        V: Clone,
+        F: FnMut([&mut V; N]),
+    {
+        debug_assert!(
+            (0..N).all(|i| (i + 1..N).all(|j| keys[i] != keys[j])),
+            "Keys passed to transact must be distinct"
+        );
+
+        let mask = self.shard_count() - 1;
+        let indices = keys.each_ref().map(|key| self.hash_key(key) as usize & mask);
+        let mut order: [usize; N] = core::array::from_fn(|i| i);
+        order.sort_unstable_by_key(|&i| indices[i]);
+
+        let _serialized = self.storage.transactions.lock();
+        let mut backoff_step = 0;
+        'retry: loop {
+            // Load every involved shard once, so keys of a shard share a version
+            let mut tables: [Option<Arc<HashTrieMapSync<K, Arc<V>>>>; N] =
+                core::array::from_fn(|_| None);
+            for group in order.chunk_by(|&a, &b| indices[a] == indices[b]) {
+                let table = self.storage.shards[indices[group[0]]].table.load_full();
+                for &i in group {
+                    tables[i] = Some(Arc::clone(&table));
+                }
+            }
+
+            let mut read = Vec::with_capacity(N);
+            for (key, table) in keys.iter().zip(tables.iter().flatten()) {
+                match table.get(key) {
+                    Some(value) => read.push(Arc::clone(value)),
+                    None => return false,
+                }
+            }
+            let Ok(read) = <[Arc<V>; N]>::try_from(read) else {
+                unreachable!("One value is read per key");
+            };
+            let mut values = read.each_ref().map(|value| value.as_ref().clone());
+            f(values.each_mut());
+            let values = values.map(Arc::new);
+
+            let mut published = false;
+            for group in order.chunk_by(|&a, &b| indices[a] == indices[b]) {
+                let shard = &self.storage.shards[indices[group[0]]];
+                let mut current =
+                    Arc::clone(tables[group[0]].as_ref().expect("Every shard was loaded"));
+                loop {
+                    let mut table = current.as_ref().clone();
+                    for &i in group {
+                        table.insert_mut(keys[i].clone(), Arc::clone(&values[i]));
+                    }
+                    let new_arc = Arc::new(table);
+                    if Arc::ptr_eq(&current, &shard.table.compare_and_swap(&current, new_arc)) {
+                        break;
+                    }
+                    backoff(&mut backoff_step);
+
+                    current = shard.table.load_full();
+                    let unchanged = group.iter().all(|&i| {
+                        current
+                            .get(&keys[i])
+                            .is_some_and(|value| Arc::ptr_eq(value, &read[i]))
+                    });
+                    if !unchanged && !published {
+                        continue 'retry;
+                    }
+                }
+                published = true;
+            }
+            return true;
+        }
+    }
+
+    /// Replace every value equal to `old` with `new`.
+    ///
+    /// Each shard is updated with a single CAS, so all matching entries of a
+    /// shard change together. The replaced entries share the same `Arc` of `new`.
+    ///
+    /// # Arguments
+    /// * `old` - The value to look for
+    /// * `new` - The value to store instead
+    ///
+    /// # Returns
+    /// The number of replaced values
+    pub fn replace_all(&self, old: &V, new: V) -> usize
+    where
+        V: PartialEq,
+    {
+        let new_value = Arc::new(new);
+        let mut replaced = 0;
+
+        for shard in self.storage.shards.iter() {
+            let mut backoff_step = 0;
+            loop {
+                let old_arc = shard.table.load();
+                let mut new_table = old_arc.as_ref().clone();
+                let mut count = 0;
+                for (k, v) in old_arc.iter() {
+                    if v.as_ref() == old {
+                        new_table.insert_mut(k.clone(), Arc::clone(&new_value));
+                        count += 1;
+                    }
+                }
+
+                if count == 0 {
+                    break;
+                }
+
+                if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, Arc::new(new_table))) {
+                    replaced += count;
+                    break;
+                }
+                backoff(&mut backoff_step);
+            }
+        }
+        replaced
+    }
+
+    /// Remove every entry whose key starts with a prefix.
+    ///
+    /// Hashing does not preserve prefixes, so every shard is scanned. The
+    /// matching entries of a shard are removed with a single CAS, but the
+    /// removal is not atomic across shards.
+    ///
+    /// # Arguments
+    /// * `prefix` - The prefix of the keys to remove
+    ///
+    /// # Returns
+    /// The number of removed entries
+    pub fn remove_prefix(&self, prefix: &str) -> usize
+    where
+        K: Borrow<str>,
+    {
+        self.remove_keys_where(|k| k.borrow().starts_with(prefix))
+    }
+
+    /// Keep only the entries whose key is in a set.
+    ///
+    /// This is the bulk reconcile primitive against an authoritative key
+    /// set. Every shard is scanned, and the removal is not atomic across
+    /// shards.
+    ///
+    /// # Arguments
+    /// * `keep` - The keys to keep
+    ///
+    /// # Returns
+    /// The number of removed entries
+    pub fn retain_keys_in<Q, S2>(&self, keep: &HashSet<Q, S2>) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash,
+        S2: BuildHasher,
+    {
+        self.remove_keys_where(|k| !keep.contains(k.borrow()))
+    }
+
+    /// Remove the entries whose key is in a set.
+    ///
+    /// Every shard is scanned, and the removal is not atomic across shards.
+    ///
+    /// # Arguments
+    /// * `drop` - The keys to remove
+    ///
+    /// # Returns
+    /// The number of removed entries
+    pub fn remove_keys_in<Q, S2>(&self, drop: &HashSet<Q, S2>) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash,
+        S2: BuildHasher,
+    {
+        self.remove_keys_where(|k| drop.contains(k.borrow()))
+    }
+
+    /// Remove the entries whose key matches a predicate, one shard at a time.
+    fn remove_keys_where<F>(&self, pred: F) -> usize
+    where
+        F: Fn(&K) -> bool,
+    {
+        let mut removed = 0;
+
+        for shard in self.storage.shards.iter() {
+            let mut backoff_step = 0;
+            loop {
+                let old_arc = shard.table.load();
+                let mut new_table = old_arc.as_ref().clone();
+                let mut count = 0;
+                for k in old_arc.keys() {
+                    if pred(k) {
+                        new_table.remove_mut(k);
+                        count += 1;
+                    }
+                }
+
+                if count == 0 {
+                    break;
+                }
+
+                if Arc::ptr_eq(&old_arc, &shard.table.compare_and_swap(&old_arc, Arc::new(new_table))) {
+                    self.storage.shard_decrement(count);
+                    removed += count;
+                    break;
+                }
+                backoff(&mut backoff_step);
+            }
+        }
+        removed
+    }
+
+    /// Iterate the entries of the shards in `[start, end)`.
+    ///
+    /// Meant for workers that each own a contiguous range of shards: only
+    /// the shards of the range are loaded, and values are shared rather than
+    /// cloned. Entries are a snapshot per shard, not of the whole range.
+    ///
+    /// # Arguments
+    /// * `start` - The index of the first shard
+    /// * `end` - The index past the last shard, at most `shard_count()`
+    ///
+    /// # Returns
+    /// An iterator over the entries of the range
+    ///
+    /// # Panics
+    /// Panics if `start > end` or `end > shard_count()`
+    pub fn iter_shard_range(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> impl Iterator<Item = (K, MaybeArc<V>)> + '_ {
+        assert!(
+            start <= end && end <= self.shard_count(),
+            "Shard range out of range"
+        );
+        self.storage
+            .shards
+            .iter()
+            .skip(start)
+            .take(end - start)
+            .flat_map(|shard| {
+                let table = shard.table.load();
+                table
+                    .iter()
+                    .map(|(k, v)| (k.clone(), MaybeArc::Shared(Arc::clone(v))))
+                    .collect::<Vec<_>>()
+            })
+    }
+
+    /// Iterate over owned clones of all entries.
+    ///
+    /// Built on [`iter_shard_range`](Self::iter_shard_range) over every
+    /// shard, so `map.entries_owned().collect::<HashMap<_, _>>()` moves the
+    /// contents into a standard map. Entries are a snapshot per shard.
+    ///
+    /// # Returns
+    /// An iterator over the entries, with values unwrapped from `MaybeArc`
+    pub fn entries_owned(&self) -> impl Iterator<Item = (K, V)> + '_
+    where
+        V: Clone,
+    {
+        self.iter_shard_range(0, self.shard_count()).map(|(k, v)| (k, v.into_owned()))
+    }
+
+    /// Remove and return all entries, leaving fresh empty shards behind.
+    ///
+    /// Each shard table is swapped out for an empty one, so producers keep
+    /// writing to the new tables while the old contents are collected.
+    /// Shards are swapped one at a time, so the result is not a snapshot
+    /// across shards. Values still shared with readers are cloned.
+    ///
+    /// # Returns
+    /// All entries that were in the map
+    pub fn take_all(&self) -> Vec<(K, V)>
+    where
+        V: Clone,
+    {
+        let mut shared = Vec::new();
+        for shard in self.storage.shards.iter() {
+            let old = shard.table.swap(Arc::new(HashTrieMap::new_sync()));
+            self.storage.shard_decrement(old.size());
+            shared.extend(old.iter().map(|(k, v)| (k.clone(), Arc::clone(v))));
+        }
+        // The old tables are dropped, so most values are no longer shared
+        shared
+            .into_iter()
+            .map(|(k, v)| (k, Arc::unwrap_or_clone(v)))
+            .collect()
+    }
+
+    /// Settle every shard on its current table version.
+    ///
+    /// Each shard table is loaded and stored back unchanged, which makes the
+    /// underlying `ArcSwap` pay back the fast-path references lent to readers
+    /// of the current version. The map never retains older versions itself,
+    /// so this does not change the contents and only helps old versions be
+    /// freed as soon as their last holder lets go.
+    ///
+    /// Memory growth under write churn usually comes from long-lived read
+    /// handles instead: every loaded shard table keeps its whole version
+    /// alive, and every `MaybeArc` returned by `get` keeps its value alive.
+    /// Keep them short-lived rather than holding them across writes.
+    pub fn compact_versions(&self) {
+        for shard in self.storage.shards.iter() {
+            let current = shard.table.load_full();
+            // A racing writer already replaced the version, nothing to settle
+            let _ = shard.table.compare_and_swap(&current, Arc::clone(&current));
+        }
+    }
+
+    /// Swap the contents of two maps, shard by shard.
+    ///
+    /// Each pair of shard tables is exchanged by pointer, without copying any
+    /// entry, and the entry counters are exchanged afterwards. Readers of
+    /// either map see a shard either before or after the swap, never a mix,
+    /// but the swap is not atomic across shards: a reader iterating the map
+    /// during the swap may see some shards swapped and others not.
+    ///
+    /// Writes racing with the swap may be lost or counted in the wrong map,
+    /// so the swap is meant for blue/green replacement once the writers of
+    /// both maps are quiescent.
+    ///
+    /// Both maps must hash keys the same way, for example a map and its
+    /// [`fork`](Self::fork), or maps built with the same seeded hasher.
+    /// Otherwise the swapped entries sit in the wrong shards and lookups miss.
+    ///
+    /// # Arguments
+    /// * `other` - The map to swap the contents with
+    ///
+    /// # Panics
+    /// Panics if the maps have a different number of shards
+    pub fn swap_contents(&self, other: &Self) {
+        assert_eq!(
+            self.shard_count(),
+            other.shard_count(),
+            "Maps must have the same number of shards"
+        );
+        if core::ptr::eq(self, other) {
+            return;
+        }
+
+        for (mine, theirs) in self.storage.shards.iter().zip(other.storage.shards.iter()) {
+            let old = mine.table.swap(theirs.table.load_full());
+            theirs.table.store(old);
+        }
+
+        let count = self.storage.count.swap(
+            other.storage.count.load(Ordering::Acquire),
+            Ordering::AcqRel,
+        );
+        other.storage.count.store(count, Ordering::Release);
     }
 }
 
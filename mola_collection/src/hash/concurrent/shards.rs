@@ -0,0 +1,100 @@
+//! The slice of shards backing a sharded storage.
+//!
+//! Shards are padded to a cache line by default, so that threads working on
+//! neighbouring shards do not invalidate each other's cache lines. On memory
+//! constrained targets with many shards, the padding can be dropped and the
+//! shards packed next to each other instead.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use crossbeam_utils::CachePadded;
+
+/// A fixed slice of shards, either padded to a cache line each or packed.
+pub(crate) enum Shards<T> {
+    Padded(Box<[CachePadded<T>]>),
+    Packed(Box<[T]>),
+}
+
+impl<T> Shards<T> {
+    /// Build a slice of padded or packed shards.
+    pub(crate) fn new(shards: impl Iterator<Item = T>, padded: bool) -> Self {
+        if padded {
+            Self::padded(shards)
+        } else {
+            Self::packed(shards)
+        }
+    }
+
+    /// Build a slice of padded shards.
+    pub(crate) fn padded(shards: impl Iterator<Item = T>) -> Self {
+        Self::Padded(
+            shards
+                .map(CachePadded::new)
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+        )
+    }
+
+    /// Build a slice of packed shards.
+    pub(crate) fn packed(shards: impl Iterator<Item = T>) -> Self {
+        Self::Packed(shards.collect::<Vec<_>>().into_boxed_slice())
+    }
+
+    /// Move the shards into a padded or packed slice.
+    pub(crate) fn with_cache_padding(self, padded: bool) -> Self {
+        match (self, padded) {
+            (Self::Packed(shards), true) => Self::padded(shards.into_vec().into_iter()),
+            (Self::Padded(shards), false) => {
+                Self::packed(shards.into_vec().into_iter().map(CachePadded::into_inner))
+            }
+            (shards, _) => shards,
+        }
+    }
+
+    /// Check if every shard is padded to a cache line.
+    pub(crate) fn is_padded(&self) -> bool {
+        matches!(self, Self::Padded(_))
+    }
+
+    /// Get the number of shards.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Padded(shards) => shards.len(),
+            Self::Packed(shards) => shards.len(),
+        }
+    }
+
+    /// Get the number of bytes taken by the shards themselves.
+    ///
+    /// Memory owned by the shards, such as their tables, is not included.
+    pub(crate) fn size_in_bytes(&self) -> usize {
+        match self {
+            Self::Padded(shards) => size_of_val::<[CachePadded<T>]>(shards),
+            Self::Packed(shards) => size_of_val::<[T]>(shards),
+        }
+    }
+
+    /// Iterate over the shards in index order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        let (padded, packed) = match self {
+            Self::Padded(shards) => (&shards[..], &[][..]),
+            Self::Packed(shards) => (&[][..], &shards[..]),
+        };
+        padded.iter().map(|shard| &**shard).chain(packed.iter())
+    }
+}
+
+impl<T> Index<usize> for Shards<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        match self {
+            Self::Padded(shards) => &shards[index],
+            Self::Packed(shards) => &shards[index],
+        }
+    }
+}
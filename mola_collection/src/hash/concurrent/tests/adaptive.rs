@@ -0,0 +1,32 @@
+use super::super::adaptive::AdaptiveLockedMap;
+
+#[test]
+fn test_adaptive_grows_shard_count() {
+    let map = AdaptiveLockedMap::<i32, i32>::with_settings_and_hasher(
+        2,
+        4,
+        64,
+        hashbrown::DefaultHashBuilder::default(),
+    );
+    assert_eq!(map.shard_count(), 2);
+
+    for i in 0..8 {
+        map.insert(i, i);
+    }
+    assert_eq!(map.shard_count(), 2);
+
+    for i in 8..100 {
+        assert_eq!(map.insert(i, i), None);
+    }
+    assert_eq!(map.shard_count(), 32);
+    assert_eq!(map.len(), 100);
+    assert!((0..100).all(|i| map.get(&i) == Some(i)));
+
+    // The shard count is capped
+    for i in 100..1000 {
+        map.insert(i, i);
+    }
+    assert_eq!(map.shard_count(), 64);
+    assert_eq!(map.remove(&0), Some(0));
+    assert_eq!(map.len(), 999);
+}
@@ -0,0 +1,52 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use super::super::arc_locked::ArcLockedMap;
+use super::super::prelude::*;
+
+static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+struct Blob(Vec<u8>);
+
+impl Clone for Blob {
+    fn clone(&self) -> Self {
+        CLONES.fetch_add(1, Ordering::Relaxed);
+        Blob(self.0.clone())
+    }
+}
+
+#[test]
+fn test_arc_locked_get_is_shared() {
+    let map = ArcLockedMap::<i32, Blob>::new();
+    assert!(map.insert(1, Blob(alloc::vec![7; 1024])).is_none());
+
+    let first = map.get(&1).unwrap();
+    let second = map.get(&1).unwrap();
+    assert!(first.is_shared());
+    assert_eq!(first.0.len(), 1024);
+    assert_eq!(CLONES.load(Ordering::Relaxed), 0);
+
+    // Both reads point at the stored value
+    let (first, second) = (first.try_shared().unwrap(), second.try_shared().unwrap());
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(map.view(&1, |_, v| v.0[0]), Some(7));
+
+    // Readers keep the old value alive across a replacement
+    let old = map
+        .insert(1, Blob(Vec::new()))
+        .unwrap()
+        .try_shared()
+        .unwrap();
+    assert!(Arc::ptr_eq(&old, &first));
+    assert!(map.get(&1).unwrap().0.is_empty());
+
+    let shared = Arc::new(Blob(alloc::vec![1]));
+    assert!(map.insert_arc(2, Arc::clone(&shared)).is_none());
+    assert!(Arc::ptr_eq(
+        &map.remove(&2).unwrap().try_shared().unwrap(),
+        &shared
+    ));
+    assert_eq!(map.len(), 1);
+    assert_eq!(CLONES.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,61 @@
+use super::super::bounded::{BoundedLockedMap, CountMinSketch};
+
+#[test]
+fn test_bounded_evicts_rarely_accessed_keys() {
+    let map = BoundedLockedMap::<i32, i32>::new(4);
+    for i in 0..4 {
+        map.insert(i, i);
+    }
+    // Keys 0 and 1 are hot, keys 2 and 3 are only accessed on insert
+    for _ in 0..10 {
+        assert_eq!(map.get(&0), Some(0));
+        assert_eq!(map.get(&1), Some(1));
+    }
+    assert!(map.frequency(&0) > map.frequency(&2));
+
+    map.insert(4, 4);
+    assert_eq!(map.len(), 4);
+    assert!(map.contains_key(&0) && map.contains_key(&1));
+    assert!(!(map.contains_key(&2) && map.contains_key(&3)));
+
+    map.insert(5, 5);
+    assert_eq!(map.len(), 4);
+    assert!(map.contains_key(&0) && map.contains_key(&1));
+}
+
+#[test]
+fn test_bounded_without_sketch() {
+    let map = BoundedLockedMap::<i32, i32>::with_sketch_width_and_hasher(
+        2,
+        None,
+        hashbrown::DefaultHashBuilder::default(),
+    );
+    map.insert(1, 1);
+    map.insert(2, 2);
+    assert_eq!(map.insert(2, 20), Some(2));
+    map.insert(3, 3);
+
+    assert_eq!(map.len(), 2);
+    assert!(map.contains_key(&3));
+    assert_eq!(map.frequency(&3), 0);
+    assert_eq!(map.remove(&3), Some(3));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_sketch_decays_periodically() {
+    let sketch = CountMinSketch::with_width(16);
+    for _ in 0..100 {
+        sketch.increment(7);
+    }
+    assert_eq!(sketch.estimate(7), 100);
+
+    // The 160th access reaches the decay period of 10 accesses per counter
+    for _ in 0..60 {
+        sketch.increment(7);
+    }
+    assert_eq!(sketch.estimate(7), 80);
+
+    sketch.decay();
+    assert_eq!(sketch.estimate(7), 40);
+}
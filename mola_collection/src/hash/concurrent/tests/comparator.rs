@@ -0,0 +1,80 @@
+extern crate std;
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use hashbrown::DefaultHashBuilder;
+use std::thread;
+
+use super::super::comparator::{ComparatorLockedMap, KeyComparator};
+
+#[derive(Default)]
+struct CaseInsensitive(DefaultHashBuilder);
+
+impl KeyComparator<String> for CaseInsensitive {
+    fn hash(&self, key: &String) -> u64 {
+        let mut hasher = self.0.build_hasher();
+        for byte in key.bytes() {
+            byte.to_ascii_lowercase().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    fn eq(&self, a: &String, b: &String) -> bool {
+        a.eq_ignore_ascii_case(b)
+    }
+}
+
+#[test]
+fn test_comparator_case_insensitive_keys() {
+    let map = ComparatorLockedMap::<String, i32, _>::with_comparator(CaseInsensitive::default());
+    assert_eq!(map.insert("Foo".to_string(), 1), None);
+    assert_eq!(map.insert("foo".to_string(), 2), Some(1));
+    assert_eq!(map.len(), 1);
+
+    // The first inserted key is kept
+    assert_eq!(
+        map.view(&"FOO".to_string(), |k, v| (k.clone(), *v)),
+        Some(("Foo".to_string(), 2))
+    );
+    assert!(map.contains_key(&"fOo".to_string()));
+    assert!(!map.contains_key(&"bar".to_string()));
+
+    assert_eq!(map.remove(&"FOO".to_string()), Some(("Foo".to_string(), 2)));
+    assert!(map.is_empty());
+    assert_eq!(map.get(&"foo".to_string()), None);
+}
+
+#[test]
+fn test_comparator_concurrent_count() {
+    let map = Arc::new(
+        ComparatorLockedMap::<String, usize, _>::with_shards_and_comparator(
+            4,
+            CaseInsensitive::default(),
+        ),
+    );
+
+    // Every key is inserted twice with different cases, and half are removed
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                for i in 0..250 {
+                    let key = format!("key{t}-{i}");
+                    map.insert(key.to_uppercase(), i);
+                    map.insert(key.clone(), i);
+                    if i % 2 == 0 {
+                        assert_eq!(map.remove(&key).map(|(_, v)| v), Some(i));
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(map.len(), 500);
+}
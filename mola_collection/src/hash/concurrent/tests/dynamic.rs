@@ -0,0 +1,35 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::super::dynamic::DynConcurrentMap;
+use super::super::locked::LockedMap;
+use super::super::rcu::HamtMap;
+
+#[test]
+fn test_boxed_maps_share_the_dyn_api() {
+    let maps: Vec<Box<dyn DynConcurrentMap<String, i32>>> = vec![
+        Box::new(LockedMap::<String, i32>::new()),
+        Box::new(HamtMap::<String, i32>::new()),
+    ];
+
+    for map in &maps {
+        assert!(map.is_empty());
+        assert!(map.insert("a".to_string(), 1).is_none());
+        assert_eq!(map.insert("a".to_string(), 2).map(|v| *v), Some(1));
+        map.insert("b".to_string(), 3);
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key(&"b".to_string()));
+        assert_eq!(map.get(&"a".to_string()).map(|v| *v), Some(2));
+
+        let mut seen = None;
+        assert!(map.view(&"b".to_string(), &mut |k, v| seen = Some((k.clone(), *v))));
+        assert_eq!(seen, Some(("b".to_string(), 3)));
+        assert!(!map.view(&"missing".to_string(), &mut |_, _| unreachable!()));
+
+        assert_eq!(map.remove(&"a".to_string()).map(|v| *v), Some(2));
+        assert!(map.get(&"a".to_string()).is_none());
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -0,0 +1,293 @@
+extern crate std;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use core::hash::{Hash, Hasher};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+use crate::hash::cached::{CachedHash, CachedHashBuilder};
+
+use super::super::prelude::*;
+use super::super::locked::LockedMap;
+use super::super::rcu::HamtMap;
+
+/// Exercise a map only through the bundled trait.
+fn exercise<M: ConcurrentMapExt<String, i32>>(map: &M) {
+    assert!(map.is_empty());
+    assert!(map.insert("a".to_string(), 1).is_none());
+    assert!(map.contains_key("a"));
+    assert_eq!(*map.get("a").unwrap(), 1);
+    assert_eq!(map.view("a", |_, v| *v + 1), Some(2));
+
+    map.alter("a", |v| *v += 10);
+    map.alter_entry("b".to_string(), || 0, |v| *v += 5);
+    assert_eq!(*map.get("a").unwrap(), 11);
+    assert_eq!(*map.get("b").unwrap(), 5);
+    assert_eq!(map.len(), 2);
+
+    assert_eq!(*map.remove("a").unwrap(), 11);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_concurrent_map_ext() {
+    exercise(&LockedMap::<String, i32>::new());
+    exercise(&HamtMap::<String, i32>::new());
+}
+
+/// Increment a `u8` counter past its bound through the counter helpers.
+fn exercise_counter<M: ConcurrentMapExt<i32, u8>>(map: &M) {
+    assert_eq!(map.increment_saturating(1, 200), 200);
+    assert_eq!(map.increment_saturating(1, 50), 250);
+    assert_eq!(map.increment_saturating(1, 10), 255);
+    assert_eq!(map.increment_saturating(1, 1), 255);
+
+    assert_eq!(map.increment_checked(2, 250), Some(250));
+    assert_eq!(map.increment_checked(2, 5), Some(255));
+    assert_eq!(map.increment_checked(2, 1), None);
+    assert_eq!(map.view(&2, |_, v| *v), Some(255));
+}
+
+#[test]
+fn test_counter_map() {
+    exercise_counter(&LockedMap::<i32, u8>::new());
+    exercise_counter(&HamtMap::<i32, u8>::new());
+}
+
+/// Increment fresh keys from many threads, expecting no increment to be lost.
+fn exercise_counter_concurrent<M: ConcurrentMapExt<i32, u64>>(map: &M) {
+    thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    map.increment_saturating(1, 1);
+                    assert!(map.increment_checked(2, 2).is_some());
+                }
+            });
+        }
+    });
+    assert_eq!(map.view(&1, |_, v| *v), Some(8000));
+    assert_eq!(map.view(&2, |_, v| *v), Some(16_000));
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_counter_map_concurrent() {
+    exercise_counter_concurrent(&LockedMap::<i32, u64>::new());
+    exercise_counter_concurrent(&HamtMap::<i32, u64>::new());
+}
+
+/// Bump one key from many threads, expecting the sum to stop at the cap.
+fn exercise_bump<M: ConcurrentMapExt<i32, u64>>(map: &M) {
+    let num_threads = 8;
+    let bumps_per_thread = 1000;
+
+    // 8 * 1000 * 3 = 24000 stays under the first cap
+    thread::scope(|s| {
+        for _ in 0..num_threads {
+            s.spawn(|| {
+                for _ in 0..bumps_per_thread {
+                    assert!(map.bump(1, 3, 30_000) <= 30_000);
+                }
+            });
+        }
+    });
+    assert_eq!(map.view(&1, |_, v| *v), Some(24_000));
+
+    // Another 24000 would overshoot the second cap
+    thread::scope(|s| {
+        for _ in 0..num_threads {
+            s.spawn(|| {
+                for _ in 0..bumps_per_thread {
+                    assert!(map.bump(1, 3, 40_000) <= 40_000);
+                }
+            });
+        }
+    });
+    assert_eq!(map.view(&1, |_, v| *v), Some(40_000));
+    assert_eq!(map.bump(2, 7, 5), 5);
+}
+
+#[test]
+fn test_bump() {
+    exercise_bump(&LockedMap::<i32, u64>::new());
+    exercise_bump(&HamtMap::<i32, u64>::new());
+}
+
+static KEY_HASHES: AtomicUsize = AtomicUsize::new(0);
+
+/// A large key counting how many times it is hashed.
+#[derive(Clone, PartialEq, Eq)]
+struct CountedKey(Vec<u8>);
+
+impl Hash for CountedKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        KEY_HASHES.fetch_add(1, Ordering::Relaxed);
+        self.0.hash(state);
+    }
+}
+
+/// Look a cached key up repeatedly, expecting its hash to be computed once.
+fn exercise_cached_hash<M: ConcurrentMapExt<CachedHash<CountedKey>, i32>>(
+    map: &M,
+    hash_builder: &CachedHashBuilder,
+) {
+    let before = KEY_HASHES.load(Ordering::Relaxed);
+    let key = hash_builder.cache(CountedKey(vec![7; 4096]));
+    let other = hash_builder.cache(CountedKey(vec![8; 4096]));
+    assert_eq!(KEY_HASHES.load(Ordering::Relaxed), before + 2);
+
+    map.insert(key.clone(), 1);
+    for _ in 0..100 {
+        assert_eq!(map.view(&key, |_, v| *v), Some(1));
+        assert!(!map.contains_key(&other));
+    }
+    map.alter(&key, |v| *v += 1);
+    assert_eq!(*map.remove(&key).unwrap(), 2);
+    assert_eq!(KEY_HASHES.load(Ordering::Relaxed), before + 2);
+}
+
+#[test]
+fn test_cached_hash_keys() {
+    let hash_builder = CachedHashBuilder::default();
+    exercise_cached_hash(
+        &LockedMap::with_shards_and_capacity_and_hasher(8, 0, hash_builder.clone()),
+        &hash_builder,
+    );
+    exercise_cached_hash(
+        &HamtMap::with_shards_and_hasher(8, hash_builder.clone()),
+        &hash_builder,
+    );
+}
+
+/// Drain a map with `take_all` while producers insert, expecting every
+/// insert to be taken exactly once.
+fn exercise_take_all<M, F>(map: &M, take_all: F)
+where
+    M: ConcurrentMapExt<usize, usize>,
+    F: Fn(&M) -> Vec<(usize, usize)> + Sync,
+{
+    let num_producers = 4;
+    let items_per_producer = 2000;
+    let done = AtomicBool::new(false);
+
+    let mut taken = thread::scope(|s| {
+        let producers: Vec<_> = (0..num_producers)
+            .map(|i| {
+                s.spawn(move || {
+                    for j in 0..items_per_producer {
+                        let key = i * items_per_producer + j;
+                        map.insert(key, key);
+                    }
+                })
+            })
+            .collect();
+        let consumer = s.spawn(|| {
+            let mut taken = Vec::new();
+            while !done.load(Ordering::Acquire) {
+                taken.extend(take_all(map));
+            }
+            taken
+        });
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        done.store(true, Ordering::Release);
+        let mut taken = consumer.join().unwrap();
+        taken.extend(take_all(map));
+        taken
+    });
+
+    taken.sort_unstable();
+    let expected: Vec<_> = (0..num_producers * items_per_producer)
+        .map(|key| (key, key))
+        .collect();
+    assert_eq!(taken, expected);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_take_all() {
+    exercise_take_all(&LockedMap::<usize, usize>::new(), LockedMap::take_all);
+    exercise_take_all(&HamtMap::<usize, usize>::new(), HamtMap::take_all);
+}
+
+/// Check that the stats of a map stay consistent through inserts and removals.
+fn exercise_stats<M, F>(map: &M, stats: F)
+where
+    M: ConcurrentMapExt<usize, usize>,
+    F: Fn(&M) -> MapStats,
+{
+    let check = |expected_len| {
+        let snapshot = stats(map);
+        assert_eq!(snapshot.is_empty, snapshot.len == 0);
+        assert_eq!(snapshot.len, expected_len);
+        assert_eq!(snapshot.len, map.len());
+        snapshot
+    };
+
+    let empty = check(0);
+    assert!(empty.is_empty);
+    for i in 0..100 {
+        map.insert(i, i);
+    }
+    map.insert(5, 50);
+    check(100);
+    for i in 0..60 {
+        map.remove(&i);
+    }
+    map.remove(&1000);
+    check(40);
+    for i in 60..100 {
+        map.remove(&i);
+    }
+    assert!(check(0).is_empty);
+}
+
+#[test]
+fn test_stats() {
+    let locked = LockedMap::<usize, usize>::new();
+    exercise_stats(&locked, LockedMap::stats);
+    assert_eq!(locked.stats().shard_count, locked.shard_count());
+    let rcu = HamtMap::<usize, usize>::new();
+    exercise_stats(&rcu, HamtMap::stats);
+    assert_eq!(rcu.stats().shard_count, rcu.shard_count());
+}
+
+#[test]
+fn test_entries_owned_into_std_map() {
+    use std::collections::HashMap;
+
+    let expected = (0..500).map(|i| (i, i.to_string())).collect::<HashMap<_, _>>();
+
+    let locked = LockedMap::<i32, String>::new();
+    let rcu = HamtMap::<i32, String>::new();
+    for (k, v) in &expected {
+        locked.insert(*k, v.clone());
+        rcu.insert(*k, v.clone());
+    }
+    assert_eq!(locked.entries_owned().collect::<HashMap<_, _>>(), expected);
+    assert_eq!(rcu.entries_owned().collect::<HashMap<_, _>>(), expected);
+}
+
+#[derive(Debug, PartialEq)]
+struct Missing(&'static str);
+
+/// Sum two values, propagating a miss with `?`.
+fn sum<M: ConcurrentMapExt<String, i32>>(map: &M, a: &str, b: &str) -> Result<i32, Missing> {
+    let a = map.get_or_err(a, || Missing("a"))?;
+    let b = map.get_or_err(b, || Missing("b"))?;
+    Ok(*a + *b)
+}
+
+#[test]
+fn test_get_or_err() {
+    let map = LockedMap::<String, i32>::new();
+    map.insert("x".to_string(), 1);
+    map.insert("y".to_string(), 2);
+
+    assert_eq!(sum(&map, "x", "y"), Ok(3));
+    assert_eq!(sum(&map, "x", "z"), Err(Missing("b")));
+    assert_eq!(sum(&map, "z", "y"), Err(Missing("a")));
+}
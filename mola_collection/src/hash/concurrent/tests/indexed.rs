@@ -0,0 +1,64 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use super::super::indexed::SecondaryIndex;
+
+struct User {
+    name: String,
+    city: String,
+}
+
+fn user(name: &str, city: &str) -> User {
+    User {
+        name: name.to_string(),
+        city: city.to_string(),
+    }
+}
+
+fn sorted(mut keys: Vec<u32>) -> Vec<u32> {
+    keys.sort_unstable();
+    keys
+}
+
+#[test]
+fn test_secondary_index_lookup() {
+    let users = SecondaryIndex::new(|user: &User| user.city.clone());
+    users.insert_indexed(1, user("ada", "london"));
+    users.insert_indexed(2, user("alan", "london"));
+    users.insert_indexed(3, user("grace", "new york"));
+    users.insert_indexed(4, user("linus", "helsinki"));
+
+    assert_eq!(users.len(), 4);
+    assert_eq!(sorted(users.lookup("london")), [1, 2]);
+    assert_eq!(users.lookup("new york"), [3]);
+    assert!(users.lookup("paris").is_empty());
+    assert_eq!(
+        users.view(&3, |_, u| u.name.clone()),
+        Some("grace".to_string())
+    );
+
+    // Moving a user re-indexes it under its new city
+    let old = users.insert_indexed(2, user("alan", "manchester"));
+    assert_eq!(old.map(|u| u.city), Some("london".to_string()));
+    assert_eq!(users.lookup("london"), [1]);
+    assert_eq!(users.lookup("manchester"), [2]);
+
+    // Replacing a value with the same index key keeps a single entry
+    users.insert_indexed(1, user("ada lovelace", "london"));
+    assert_eq!(users.lookup("london"), [1]);
+
+    // Removing the last user of a city empties its entry
+    assert_eq!(
+        users.remove_indexed(&4).map(|u| u.name),
+        Some("linus".to_string())
+    );
+    assert!(users.remove_indexed(&4).is_none());
+    assert!(users.lookup("helsinki").is_empty());
+    assert!(!users.contains_key(&4));
+    assert_eq!(
+        users.remove_indexed(&1).map(|u| u.name),
+        Some("ada lovelace".to_string())
+    );
+    assert!(users.lookup("london").is_empty());
+    assert_eq!(users.len(), 2);
+}
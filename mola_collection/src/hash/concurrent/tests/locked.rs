@@ -1,15 +1,21 @@
 extern crate std;
 use alloc::{
+    borrow::Cow,
+    boxed::Box,
     format,
     string::{String, ToString},
     sync::Arc,
+    vec::Vec,
 };
+use core::hash::{BuildHasher, Hasher};
+use core::sync::atomic::{AtomicUsize, Ordering};
 use std::{thread, vec};
 
-use crate::hash::concurrent::locked::LockedMapBuilder;
+use crate::hash::concurrent::locked::{CountingMode, LockedMapBuilder};
 
 use super::super::prelude::*;
-use super::super::locked::LockedMap;
+use super::super::lock_order::LockOrderToken;
+use super::super::locked::{LockedMap, LockedShard};
 
 #[test]
 fn test_alter_entry_updates_count() {
@@ -195,6 +201,10 @@ fn test_builder_pattern() {
 fn test_concurrency() {
     let shard_count =
         (std::thread::available_parallelism().map_or(1, usize::from) * 4).next_power_of_two();
+    check_concurrency(shard_count);
+}
+
+fn check_concurrency(shard_count: usize) {
     let map: Arc<LockedMap<usize, usize>> = Arc::new(
         LockedMapBuilder::new()
             .with_shards(shard_count)
@@ -257,3 +267,1022 @@ fn test_concurrency() {
 
     assert!(map.is_empty());
 }
+
+#[test]
+fn test_get_or_compute_single_flight() {
+    let map: Arc<LockedMap<i32, usize>> = Arc::new(LockedMap::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let num_threads = 8;
+
+    let mut handles = vec![];
+    for _ in 0..num_threads {
+        let map_clone = Arc::clone(&map);
+        let calls_clone = Arc::clone(&calls);
+        let handle = thread::spawn(move || {
+            let value = map_clone.get_or_compute_single_flight(1, || {
+                calls_clone.fetch_add(1, Ordering::SeqCst);
+                thread::sleep(std::time::Duration::from_millis(10));
+                42
+            });
+            assert_eq!(*value, 42);
+        });
+        handles.push(handle);
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // The compute closure must run exactly once for the contended key
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.view(&1, |_, v| *v), Some(42));
+}
+
+#[test]
+fn test_view_non_clone_value() {
+    type Callback = Box<dyn Fn() -> i32 + Send + Sync>;
+
+    let map = LockedMap::<String, Callback>::new();
+    map.insert("answer".to_string(), Box::new(|| 42));
+
+    // The stored trait object is invoked through `view` without cloning it
+    assert_eq!(map.view("answer", |_, f| f()), Some(42));
+    assert_eq!(map.view("missing", |_, f| f()), None);
+
+    // `alter` gives in-place mutable access without cloning as well
+    map.alter("answer", |f| *f = Box::new(|| 7));
+    assert_eq!(map.view("answer", |_, f| f()), Some(7));
+}
+
+/// A deliberately bad hasher that sends every key to the same shard.
+#[derive(Default)]
+struct ConstantHasher;
+
+impl Hasher for ConstantHasher {
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    fn write(&mut self, _bytes: &[u8]) {}
+}
+
+impl BuildHasher for ConstantHasher {
+    type Hasher = Self;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        ConstantHasher
+    }
+}
+
+#[test]
+fn test_rehash_with() {
+    let map = LockedMap::with_shards_and_capacity_and_hasher(8, 0, ConstantHasher);
+    for i in 0..64 {
+        map.insert(i, i * 2);
+    }
+
+    let skewed = map.diagnose_distribution();
+    assert_eq!(skewed.max, 64);
+    assert_eq!(skewed.empty_shards, 7);
+
+    let rehashed = map.rehash_with(hashbrown::DefaultHashBuilder::default());
+    assert!(map.is_empty());
+    assert_eq!(rehashed.len(), 64);
+    assert_eq!(rehashed.shard_count(), 8);
+
+    let balanced = rehashed.diagnose_distribution();
+    assert!(balanced.max < skewed.max);
+    assert!(balanced.empty_shards < skewed.empty_shards);
+
+    for i in 0..64 {
+        assert_eq!(rehashed.view(&i, |_, v| *v), Some(i * 2));
+    }
+}
+
+#[derive(Clone, Default)]
+struct CountingHasher {
+    inner: hashbrown::DefaultHashBuilder,
+    built: Arc<AtomicUsize>,
+}
+
+impl BuildHasher for CountingHasher {
+    type Hasher = <hashbrown::DefaultHashBuilder as BuildHasher>::Hasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        self.built.fetch_add(1, Ordering::Relaxed);
+        self.inner.build_hasher()
+    }
+}
+
+#[test]
+fn test_reshard_reuses_stored_hashes() {
+    let hasher = CountingHasher::default();
+    let map = LockedMap::with_shards_and_capacity_and_hasher(4, 0, hasher.clone());
+    for i in 0..100 {
+        map.insert(i, i * 2);
+    }
+
+    let built = hasher.built.load(Ordering::Relaxed);
+    let resharded = map.reshard(32);
+    assert_eq!(hasher.built.load(Ordering::Relaxed), built);
+    assert_eq!(resharded.len(), 100);
+    for i in 0..100 {
+        assert_eq!(resharded.view(&i, |_, v| *v), Some(i * 2));
+    }
+
+    // A new hasher has to hash every key again
+    let rehashed = resharded.rehash_with(hasher.clone());
+    assert_eq!(hasher.built.load(Ordering::Relaxed), built + 2 * 100);
+    assert_eq!(rehashed.view(&42, |_, v| *v), Some(84));
+}
+
+#[test]
+fn test_counting_modes_after_concurrent_churn() {
+    for mode in [CountingMode::Atomic, CountingMode::Summed] {
+        let map: Arc<LockedMap<usize, usize>> = Arc::new(
+            LockedMapBuilder::new()
+                .with_shards(8)
+                .with_counting_mode(mode)
+                .build(),
+        );
+        assert_eq!(map.storage.counting_mode(), mode);
+
+        let num_threads = 8;
+        let items_per_thread = 500;
+
+        let mut handles = vec![];
+        for i in 0..num_threads {
+            let map_clone = Arc::clone(&map);
+            let handle = thread::spawn(move || {
+                for j in 0..items_per_thread {
+                    let key = i * items_per_thread + j;
+                    map_clone.insert(key, key);
+                    // Overwrite and remove every other key to churn the map
+                    map_clone.insert(key, key + 1);
+                    if j % 2 == 0 {
+                        map_clone.remove(&key);
+                    }
+                }
+            });
+            handles.push(handle);
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(map.len(), num_threads * items_per_thread / 2);
+
+        map.clear();
+        assert_eq!(map.len(), 0);
+        assert!(map.is_empty());
+    }
+}
+
+#[test]
+fn test_replace_all() {
+    let map = LockedMap::<i32, String>::new();
+    for i in 0..20 {
+        let value = if i % 4 == 0 { "old" } else { "other" };
+        map.insert(i, value.to_string());
+    }
+
+    let replaced = map.replace_all(&"old".to_string(), "new".to_string());
+    assert_eq!(replaced, 5);
+    assert_eq!(map.len(), 20);
+
+    for i in 0..20 {
+        let expected = if i % 4 == 0 { "new" } else { "other" };
+        assert_eq!(map.view(&i, |_, v| v.clone()).unwrap(), expected);
+    }
+
+    // Nothing left to replace
+    assert_eq!(map.replace_all(&"old".to_string(), "new".to_string()), 0);
+}
+
+#[test]
+fn test_remove_prefix() {
+    let map = LockedMap::<String, i32>::new();
+    map.insert("a/1".to_string(), 1);
+    map.insert("a/2".to_string(), 2);
+    map.insert("b/1".to_string(), 3);
+
+    assert_eq!(map.remove_prefix("a/"), 2);
+    assert_eq!(map.len(), 1);
+    assert!(!map.contains_key("a/1"));
+    assert!(!map.contains_key("a/2"));
+    assert_eq!(map.view("b/1", |_, v| *v), Some(3));
+
+    // Nothing left to remove
+    assert_eq!(map.remove_prefix("a/"), 0);
+}
+
+#[test]
+fn test_increment_and_check() {
+    let map = LockedMap::<&str, u64>::new();
+    let crossings = AtomicUsize::new(0);
+
+    thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    if map.increment_and_check("hits", 1, 5000) {
+                        crossings.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(crossings.load(Ordering::Relaxed), 1);
+    assert_eq!(map.view("hits", |_, v| *v), Some(8000));
+
+    // A single jump over the threshold counts as a crossing
+    assert!(map.increment_and_check("other", 10, 5));
+    assert!(!map.increment_and_check("other", 10, 5));
+}
+
+#[test]
+fn test_retain_and_remove_keys_in() {
+    use hashbrown::HashSet;
+
+    let map = LockedMap::<i32, i32>::new();
+    for i in 0..100 {
+        map.insert(i, i);
+    }
+
+    let keep = (0..30).map(|i| i * 3).collect::<HashSet<i32>>();
+    assert_eq!(map.retain_keys_in(&keep), 70);
+    assert_eq!(map.len(), 30);
+    for i in 0..100 {
+        assert_eq!(map.contains_key(&i), keep.contains(&i));
+    }
+
+    let drop = (0..10).collect::<HashSet<i32>>();
+    assert_eq!(map.remove_keys_in(&drop), 4);
+    assert_eq!(map.len(), 26);
+    assert!(!map.contains_key(&9));
+    assert!(map.contains_key(&12));
+}
+
+#[test]
+fn test_get_with_shard_len() {
+    let map = LockedMap::<i32, i32>::new();
+    for i in 0..100 {
+        map.insert(i, i * 10);
+    }
+
+    for i in 0..100 {
+        let (value, shard_len) = map.get_with_shard_len(&i).unwrap();
+        assert_eq!(*value, i * 10);
+        let index = map.shard_index_for_key(&i);
+        assert_eq!(
+            shard_len,
+            map.with_shard_by_index(index, |table| table.len())
+        );
+    }
+    assert!(map.get_with_shard_len(&100).is_none());
+}
+
+#[test]
+fn test_get_disjoint_mut() {
+    let map = LockedMap::with_shards_and_capacity_and_hasher(
+        4,
+        0,
+        hashbrown::DefaultHashBuilder::default(),
+    );
+    for i in 0..16 {
+        map.insert(i, i * 10);
+    }
+
+    {
+        // Move value between two keys atomically
+        let [a, b, missing] = map.get_disjoint_mut([&1, &9, &100]);
+        let (mut a, mut b) = (a.unwrap(), b.unwrap());
+        assert!(missing.is_none());
+        *b += *a;
+        *a = 0;
+    }
+
+    assert_eq!(map.view(&1, |_, v| *v), Some(0));
+    assert_eq!(map.view(&9, |_, v| *v), Some(100));
+
+    // Keys spanning every shard lock each shard once and release them on drop
+    let refs = map.get_disjoint_mut([&0, &1, &2, &3, &4, &5, &6, &7]);
+    assert!(refs.iter().all(Option::is_some));
+    drop(refs);
+    map.insert(0, 1);
+    assert_eq!(map.view(&0, |_, v| *v), Some(1));
+}
+
+#[test]
+#[should_panic(expected = "Keys passed to get_disjoint_mut must be distinct")]
+fn test_get_disjoint_mut_duplicate_keys() {
+    let map = LockedMap::<i32, i32>::new();
+    map.insert(1, 1);
+    let _ = map.get_disjoint_mut([&1, &1]);
+}
+
+#[test]
+fn test_with_shard_by_index() {
+    let map = LockedMap::<i32, i32>::new();
+    for i in 0..100 {
+        map.insert(i, i);
+    }
+
+    let total: usize = (0..map.shard_count())
+        .map(|index| map.with_shard_by_index(index, |table| table.len()))
+        .sum();
+    assert_eq!(total, map.len());
+
+    // Removing through the table keeps the count in sync
+    map.with_shard_by_index(0, |table| table.clear());
+    let total: usize = (0..map.shard_count())
+        .map(|index| map.with_shard_by_index(index, |table| table.len()))
+        .sum();
+    assert_eq!(total, map.len());
+}
+
+#[test]
+fn test_stored_hashes() {
+    // Long keys make rehashing expensive, which the stored hashes avoid
+    let key = |i: usize| format!("{i:0>256}");
+    let map = LockedMap::<String, usize>::new();
+    for i in 0..200 {
+        map.insert(key(i), i);
+    }
+    map.rename(&key(0), "renamed".to_string());
+    map.upsert("upserted".to_string(), 1, |_| ());
+
+    // Every entry holds the hash of its key
+    for index in 0..map.shard_count() {
+        map.with_shard_by_index(index, |table| {
+            for (hash, key, _) in table.iter() {
+                assert_eq!(*hash, map.hash_key(key));
+            }
+        });
+    }
+
+    // Lookups still find every entry after the tables grew
+    for i in 1..200 {
+        assert_eq!(map.get(&key(i)).as_deref(), Some(&i));
+    }
+    assert_eq!(map.get("renamed").as_deref(), Some(&0));
+    assert_eq!(map.get("upserted").as_deref(), Some(&1));
+    assert!(!map.contains_key(&key(0)));
+    assert_eq!(map.len(), 201);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Entry stored with a hash different from the hash of its key")]
+fn test_with_shard_by_index_wrong_hash_panics() {
+    let map = LockedMap::<i32, i32>::new();
+    map.with_shard_by_index(0, |table| {
+        table.insert_unique(0, (1, 1, 1), |(h, _, _)| *h);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Shard index out of range")]
+fn test_with_shard_by_index_out_of_range() {
+    let map = LockedMap::<i32, i32>::new();
+    map.with_shard_by_index(map.shard_count(), |_| ());
+}
+
+#[test]
+fn test_insert_or_get_race() {
+    let map: Arc<LockedMap<i32, usize>> = Arc::new(LockedMap::new());
+    let num_threads = 8;
+
+    let mut handles = vec![];
+    for i in 0..num_threads {
+        let map_clone = Arc::clone(&map);
+        handles.push(thread::spawn(move || *map_clone.insert_or_get(1, i)));
+    }
+    let observed: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // Every thread observes the single winning value
+    let winner = observed[0];
+    assert!(observed.iter().all(|&v| v == winner));
+    assert_eq!(*map.get(&1).unwrap(), winner);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_shard_routing_recipe() {
+    let map = LockedMap::<i32, i32>::new();
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+
+    let mask = map.shard_count_mask().unwrap();
+    assert_eq!(mask, map.shard_count() - 1);
+    for i in 0..1000 {
+        let hash = map.hasher().hash_one(i);
+        let index = hash as usize & mask;
+        assert!(core::ptr::eq(map.shard_for_hash(hash), map.shard_for_key(&i)));
+        assert!(map.with_shard_by_index(index, |table| {
+            table.find(hash, |(_, k, _)| *k == i).is_some()
+        }));
+    }
+}
+
+#[test]
+fn test_contains_all_and_any() {
+    let map = LockedMap::<i32, i32>::new();
+    for i in 0..50 {
+        map.insert(i, i);
+    }
+
+    let present: Vec<&i32> = [1, 7, 23, 49].iter().collect();
+    let some_missing: Vec<&i32> = [1, 7, 100, 49].iter().collect();
+    let none_present: Vec<&i32> = [100, 200, 300].iter().collect();
+
+    assert!(map.contains_all(&present));
+    assert!(map.contains_any(&present));
+
+    assert!(!map.contains_all(&some_missing));
+    assert!(map.contains_any(&some_missing));
+
+    assert!(!map.contains_all(&none_present));
+    assert!(!map.contains_any(&none_present));
+
+    // Empty key sets behave like the empty quantifiers
+    assert!(map.contains_all(&[]));
+    assert!(!map.contains_any(&[]));
+}
+
+#[test]
+fn test_remove_and_maybe_shrink() {
+    let map: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(1).build();
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    let peak = map.with_shard_by_index(0, |table| table.capacity());
+
+    for i in 0..990 {
+        assert_eq!(map.remove_and_maybe_shrink(&i, 0.25), Some(i));
+    }
+    assert_eq!(map.remove_and_maybe_shrink(&0, 0.25), None);
+    assert_eq!(map.len(), 10);
+
+    let shrunk = map.with_shard_by_index(0, |table| table.capacity());
+    assert!(shrunk < peak);
+    for i in 990..1000 {
+        assert_eq!(map.view(&i, |_, v| *v), Some(i));
+    }
+}
+
+#[test]
+fn test_clear_and_shrink() {
+    let map: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(4).build();
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    let capacity = |map: &LockedMap<i32, i32>| {
+        (0..map.shard_count())
+            .map(|index| map.with_shard_by_index(index, |table| table.capacity()))
+            .sum::<usize>()
+    };
+    assert!(capacity(&map) >= 1000);
+
+    // A plain clear keeps the capacity around
+    map.clear();
+    assert!(map.is_empty());
+    assert!(capacity(&map) >= 1000);
+
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    map.clear_and_shrink();
+    assert_eq!(map.len(), 0);
+    assert_eq!(capacity(&map), 0);
+
+    // The map is still usable afterwards
+    map.insert(1, 10);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.view(&1, |_, v| *v), Some(10));
+}
+
+#[test]
+fn test_from_shards() {
+    let hasher = hashbrown::DefaultHashBuilder::default();
+    let mut tables = vec![
+        hashbrown::HashTable::new(),
+        hashbrown::HashTable::new(),
+    ];
+    for i in 0..20 {
+        let hash = hasher.hash_one(i);
+        tables[hash as usize & 1].insert_unique(hash, (i, i * 3), |(k, _)| hasher.hash_one(k));
+    }
+
+    let map = LockedMap::from_shards(tables, hasher);
+    assert_eq!(map.shard_count(), 2);
+    assert_eq!(map.len(), 20);
+    for i in 0..20 {
+        assert_eq!(map.view(&i, |_, v| *v), Some(i * 3));
+    }
+    assert!(!map.contains_key(&20));
+}
+
+#[test]
+#[should_panic(expected = "Number of shards must be a power of two")]
+fn test_from_shards_non_power_of_two() {
+    let tables: Vec<hashbrown::HashTable<(i32, i32)>> = vec![
+        hashbrown::HashTable::new(),
+        hashbrown::HashTable::new(),
+        hashbrown::HashTable::new(),
+    ];
+    let _ = LockedMap::from_shards(tables, hashbrown::DefaultHashBuilder::default());
+}
+
+#[test]
+fn test_shard_session() {
+    let map: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(1).build();
+    map.insert(1, 10);
+    map.insert(2, 20);
+
+    let removed = map.shard_session(&1, |session| {
+        *session.get_mut(&1).unwrap() += 1;
+        assert_eq!(session.insert(3, 30), None);
+        assert_eq!(session.insert(2, 21), Some(20));
+        assert!(session.get_mut(&4).is_none());
+        session.remove(&3)
+    });
+
+    assert_eq!(removed, Some(30));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.view(&1, |_, v| *v), Some(11));
+    assert_eq!(map.view(&2, |_, v| *v), Some(21));
+    assert!(!map.contains_key(&3));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Key does not belong to the shard of this session")]
+fn test_shard_session_foreign_key() {
+    let map = LockedMap::<i32, i32>::new();
+    let foreign = (1..)
+        .find(|k| !core::ptr::eq(map.shard_for_key(k), map.shard_for_key(&0)))
+        .unwrap();
+    map.shard_session(&0, |session| session.insert(foreign, 0));
+}
+
+#[test]
+fn test_get_ref_as_cow_borrows() {
+    // Cloning would panic, so reading must borrow under the read guard
+    #[derive(Debug, PartialEq)]
+    struct NoClone(i32);
+    impl Clone for NoClone {
+        fn clone(&self) -> Self {
+            panic!("value was cloned");
+        }
+    }
+
+    let map = LockedMap::<i32, NoClone>::new();
+    map.insert(1, NoClone(10));
+
+    let value = map.get_ref(&1).unwrap();
+    let cow = value.as_cow();
+    assert!(matches!(cow, Cow::Borrowed(NoClone(10))));
+    drop(cow);
+    drop(value);
+
+    assert!(map.get_ref(&2).is_none());
+    map.insert(1, NoClone(11));
+    assert_eq!(*map.get_ref(&1).unwrap(), NoClone(11));
+
+    let borrowed = map.get_cow(&1, |cow| matches!(cow, Cow::Borrowed(NoClone(11))));
+    assert_eq!(borrowed, Some(true));
+    assert_eq!(map.get_cow(&2, |_| ()), None);
+}
+
+#[test]
+fn test_sample_keys() {
+    let map = LockedMap::<i32, i32>::new();
+    assert!(map.sample_keys(4).is_empty());
+
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    for _ in 0..map.shard_count() * 2 {
+        let keys = map.sample_keys(4);
+        assert!(!keys.is_empty() && keys.len() <= 4);
+        assert!(keys.iter().all(|k| map.contains_key(k)));
+    }
+    assert!(map.sample_keys(0).is_empty());
+
+    // Empty shards are skipped
+    let map: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(8).build();
+    map.insert(7, 7);
+    for _ in 0..8 {
+        assert_eq!(map.sample_keys(4), vec![7]);
+    }
+}
+
+#[test]
+fn test_rename() {
+    let map: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(1).build();
+    map.insert(1, 10);
+    map.insert(2, 20);
+
+    assert!(map.rename(&1, 3));
+    assert!(!map.contains_key(&1));
+    assert_eq!(map.view(&3, |_, v| *v), Some(10));
+    assert_eq!(map.len(), 2);
+
+    // Renaming onto an existing key replaces its value
+    assert!(map.rename(&3, 2));
+    assert_eq!(map.view(&2, |_, v| *v), Some(10));
+    assert_eq!(map.len(), 1);
+
+    assert!(map.rename(&2, 2));
+    assert!(!map.rename(&1, 4));
+    assert_eq!(map.len(), 1);
+
+    // Across shards
+    let map = LockedMap::<i32, i32>::new();
+    map.insert(0, 1);
+    let other = (1..)
+        .find(|k| !core::ptr::eq(map.shard_for_key(k), map.shard_for_key(&0)))
+        .unwrap();
+    assert!(map.rename(&0, other));
+    assert_eq!(map.view(&other, |_, v| *v), Some(1));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_replace_with() {
+    let map = LockedMap::<i32, i32>::new();
+    map.insert(1, 10);
+
+    let (old, new) = map.replace_with(&1, |v| v * 2).unwrap();
+    assert_eq!((*old, *new), (10, 20));
+    assert_eq!(map.view(&1, |_, v| *v), Some(20));
+
+    assert!(map.replace_with(&2, |v| v + 1).is_none());
+    assert!(!map.contains_key(&2));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_swap_value() {
+    let map = LockedMap::<i32, i32>::new();
+    map.insert(1, 10);
+
+    assert_eq!(*map.swap_value(&1, 11).unwrap(), 10);
+    assert_eq!(map.view(&1, |_, v| *v), Some(11));
+
+    assert_eq!(map.swap_value(&2, 20).err(), Some(20));
+    assert!(!map.contains_key(&2));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_insert_if_absent_race() {
+    let map: Arc<LockedMap<i32, usize>> = Arc::new(LockedMap::new());
+    let num_threads = 8;
+
+    let mut handles = vec![];
+    for i in 0..num_threads {
+        let map_clone = Arc::clone(&map);
+        handles.push(thread::spawn(move || (i, map_clone.insert_if_absent(1, i))));
+    }
+    let results: Vec<(usize, bool)> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // Exactly one thread added the key, and its value was kept
+    let winners: Vec<usize> = results
+        .iter()
+        .filter(|(_, added)| *added)
+        .map(|(i, _)| *i)
+        .collect();
+    assert_eq!(winners.len(), 1);
+    assert_eq!(map.view(&1, |_, v| *v), Some(winners[0]));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_raw_count_converges() {
+    let map: Arc<LockedMap<i32, i32>> = Arc::new(LockedMap::new());
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                for i in 0..100 {
+                    map.insert(t * 100 + i, i);
+                }
+                for i in 0..50 {
+                    map.remove(&(t * 100 + i));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(map.raw_count(), Some(map.len()));
+    assert_eq!(map.raw_count(), Some(200));
+
+    let summed: LockedMap<i32, i32> = LockedMapBuilder::new()
+        .with_counting_mode(CountingMode::Summed)
+        .build();
+    summed.insert(1, 1);
+    assert_eq!(summed.raw_count(), None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "shards must be locked in ascending order")]
+fn test_lock_order_violation_panics() {
+    let _high = LockOrderToken::acquire(0, 5);
+    let _low = LockOrderToken::acquire(0, 2);
+}
+
+#[test]
+fn test_lock_order_across_maps() {
+    let first =
+        LockedMap::<i32, i32>::with_shards_and_capacity_and_hasher(32, 0, Default::default());
+    let second =
+        LockedMap::<i32, i32>::with_shards_and_capacity_and_hasher(32, 0, Default::default());
+    let shard_of = |map: &LockedMap<i32, i32>, key: &i32| map.hash_key(key) as usize & 31;
+
+    // A key in a high shard of the first map, and keys in a lower shard of the second
+    let high = (0..).find(|k| shard_of(&first, k) >= 16).unwrap();
+    let low = (0..).find(|k| shard_of(&second, k) < 16).unwrap();
+    let target = (low + 1..).find(|k| shard_of(&second, k) < 16).unwrap();
+    first.insert(high, 1);
+    second.insert(low, 2);
+
+    let refs = first.get_disjoint_mut([&high]);
+    assert!(refs[0].is_some());
+    assert!(second.rename(&low, target));
+    drop(refs);
+    assert_eq!(second.view(&target, |_, v| *v), Some(2));
+}
+
+#[test]
+fn test_lock_order_canonical() {
+    {
+        let _low = LockOrderToken::acquire(0, 2);
+        let _high = LockOrderToken::acquire(0, 5);
+    }
+    // Released shards no longer constrain the order
+    let low = LockOrderToken::acquire(0, 1);
+    drop(low);
+
+    // Shards of different storages are not ordered against each other
+    {
+        let _high = LockOrderToken::acquire(0, 5);
+        let _other = LockOrderToken::acquire(1, 2);
+    }
+
+    // Multi-shard operations lock in ascending order whatever the key order
+    let map = LockedMap::<i32, i32>::new();
+    for i in 0..64 {
+        map.insert(i, i);
+    }
+    let keys: [&i32; 8] = [&63, &7, &31, &0, &12, &50, &3, &44];
+    let refs = map.get_disjoint_mut(keys);
+    assert!(refs.iter().all(Option::is_some));
+    drop(refs);
+    for i in 0..63 {
+        assert!(map.rename(&(63 - i), 100 + i));
+        assert!(map.rename(&(100 + i), 63 - i));
+    }
+    assert_eq!(map.len(), 64);
+}
+
+#[cfg(feature = "parking_lot")]
+#[test]
+fn test_parking_lot_concurrency() {
+    // The same workload on one shard, so every thread contends on a single lock and parks
+    check_concurrency(1);
+}
+
+#[test]
+fn test_iter_shard_range() {
+    let map: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(8).build();
+    for i in 0..500 {
+        map.insert(i, i * 2);
+    }
+
+    // Two workers splitting the shards see every entry exactly once
+    let mut seen: Vec<(i32, i32)> = map
+        .iter_shard_range(0, 3)
+        .chain(map.iter_shard_range(3, 8))
+        .map(|(k, v)| (k, *v))
+        .collect();
+    seen.sort_unstable();
+    assert_eq!(seen, (0..500).map(|i| (i, i * 2)).collect::<Vec<_>>());
+    assert_eq!(map.iter_shard_range(4, 4).count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Shard range out of range")]
+fn test_iter_shard_range_past_end() {
+    let map: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(8).build();
+    let _ = map.iter_shard_range(4, 9);
+}
+
+#[test]
+fn test_upsert() {
+    let map: Arc<LockedMap<i32, Vec<i32>>> = Arc::new(LockedMap::new());
+
+    // A new key takes the value without running the closure
+    assert!(map.upsert(1, vec![1], |_| panic!("Key 1 must be absent")));
+    assert!(!map.upsert(1, vec![0], |v| v.push(2)));
+    assert_eq!(map.view(&1, |_, v| v.clone()), Some(vec![1, 2]));
+    assert_eq!(map.len(), 1);
+
+    // Concurrent upserts insert once and modify the rest of the time
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || map.upsert(2, vec![i], |v| v.push(i)))
+        })
+        .collect();
+    let inserted = handles
+        .into_iter()
+        .map(|h| h.join().unwrap())
+        .filter(|inserted| *inserted)
+        .count();
+    assert_eq!(inserted, 1);
+    assert_eq!(map.view(&2, |_, v| v.len()), Some(8));
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_append_to_entry() {
+    let map: Arc<LockedMap<i32, Vec<i32>>> = Arc::new(LockedMap::new());
+    map.append_to_entry(1, [1, 2]);
+    map.append_to_entry(1, Vec::<i32>::new());
+    map.append_to_entry(1, vec![3]);
+    assert_eq!(map.view(&1, |_, v| v.clone()), Some(vec![1, 2, 3]));
+    assert_eq!(map.len(), 1);
+
+    // Concurrent appends to the same key keep every item
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                for j in 0..100 {
+                    map.append_to_entry(2, [i * 100 + j]);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let mut items = map.view(&2, |_, v| v.clone()).unwrap();
+    items.sort_unstable();
+    assert_eq!(items, (0..800).collect::<Vec<_>>());
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_same_shard() {
+    let map: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(8).build();
+    let index = map.shard_index_for_key(&0);
+    let colliding = (1..).find(|k| map.shard_index_for_key(k) == index).unwrap();
+    let apart = (1..).find(|k| map.shard_index_for_key(k) != index).unwrap();
+
+    assert!(map.same_shard(&0, &colliding));
+    assert!(!map.same_shard(&0, &apart));
+    assert!(core::ptr::eq(map.shard_for_key(&0), map.shard_for_key(&colliding)));
+    assert!(!core::ptr::eq(map.shard_for_key(&0), map.shard_for_key(&apart)));
+
+    // With a single shard every pair of keys shares it
+    let single: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(1).build();
+    assert!(single.same_shard(&0, &apart));
+}
+
+#[test]
+fn test_unpadded_shards() {
+    let padded: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(64).build();
+    let packed: LockedMap<i32, i32> = LockedMapBuilder::new()
+        .with_shards(64)
+        .with_cache_padding(false)
+        .build();
+    assert!(padded.storage.is_cache_padded());
+    assert!(!packed.storage.is_cache_padded());
+    assert_eq!(
+        packed.storage.shards_size_in_bytes(),
+        64 * core::mem::size_of::<LockedShard<i32, i32>>()
+    );
+    assert!(packed.storage.shards_size_in_bytes() < padded.storage.shards_size_in_bytes());
+
+    for i in 0..1000 {
+        packed.insert(i, i * 2);
+    }
+    packed.alter(&7, |v| *v += 1);
+    assert_eq!(packed.view(&7, |_, v| *v), Some(15));
+    assert_eq!(packed.remove_entry(&8), Some((8, 16)));
+    assert_eq!(packed.len(), 999);
+
+    // Resharding keeps the shards packed
+    let resharded = packed.reshard(128);
+    assert!(!resharded.storage.is_cache_padded());
+    assert_eq!(resharded.len(), 999);
+    assert_eq!(resharded.view(&7, |_, v| *v), Some(15));
+}
+
+#[test]
+fn test_seeded_hasher_iteration_order() {
+    let keys = (0..1000).map(|i| i * 7919 % 1000).collect::<Vec<i32>>();
+    let order = |seed| {
+        let map: LockedMap<i32, i32, _> = LockedMap::with_seeded_hasher(seed);
+        for &key in &keys {
+            map.insert(key, key);
+        }
+        map.iter_shard_range(0, map.shard_count())
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(order(42), order(42));
+    assert_eq!(order(42).len(), 1000);
+    assert_ne!(order(42), order(43));
+}
+
+#[test]
+fn test_with_capacity_hints() {
+    use crate::hash::seeded::SeededHashBuilder;
+
+    // Most of the sample lands in the shard of key 0
+    let probe: LockedMap<i32, (), _> = LockedMap::with_seeded_hasher(7);
+    let busy = probe.shard_index_for_key(&0);
+    let mut sample = (0..10_000)
+        .filter(|key| probe.shard_index_for_key(key) == busy)
+        .take(500)
+        .collect::<Vec<i32>>();
+    sample.extend(-64..0);
+
+    let map: LockedMap<i32, i32, _> =
+        LockedMap::with_capacity_hints_and_hasher(sample, SeededHashBuilder::with_seed(7));
+    assert!(map.is_empty());
+
+    let capacities = (0..map.shard_count())
+        .map(|index| map.with_shard_by_index(index, |table| table.capacity()))
+        .collect::<Vec<_>>();
+    assert!(capacities[busy] >= 500);
+    for (index, &capacity) in capacities.iter().enumerate() {
+        if index != busy {
+            assert!(capacity < capacities[busy]);
+        }
+    }
+}
+
+#[test]
+fn test_apply_fixed() {
+    use crate::hash::fixed::FixedMap;
+
+    let mut fixed: FixedMap<i32, i32, 64> = FixedMap::new();
+    for i in 0..40 {
+        fixed.insert(i, i * 3);
+    }
+    fixed.remove(&7);
+
+    let map: LockedMap<i32, i32> = LockedMapBuilder::new().with_shards(8).build();
+    map.insert(1, -1);
+    map.insert(100, 100);
+    map.apply_fixed(&fixed);
+
+    // Existing keys are overwritten, other keys are left alone
+    assert_eq!(map.len(), 40);
+    for i in (0..40).filter(|i| *i != 7) {
+        assert_eq!(map.view(&i, |_, v| *v), Some(i * 3));
+    }
+    assert!(!map.contains_key(&7));
+    assert_eq!(map.view(&100, |_, v| *v), Some(100));
+    assert_eq!(fixed.len(), 39);
+}
+
+#[test]
+fn test_get_or_try_insert_with_race() {
+    let map: Arc<LockedMap<i32, usize>> = Arc::new(LockedMap::new());
+
+    // Odd threads compute a value, even threads fail
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                let result = map
+                    .get_or_try_insert_with(1, || if i % 2 == 1 { Ok(i) } else { Err(i) })
+                    .map(|v| *v);
+                let failed = map.get_or_try_insert_with(2, || Err::<usize, _>(i));
+                assert_eq!(failed.err(), Some(i));
+                result
+            })
+        })
+        .collect();
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // Successes converge to the stored value, failures inserted nothing
+    let winner = map.view(&1, |_, v| *v).unwrap();
+    assert_eq!(winner % 2, 1);
+    assert!(results.iter().all(|r| *r == Ok(winner) || r.is_err()));
+    assert!(results.contains(&Ok(winner)));
+    assert!(!map.contains_key(&2));
+    assert_eq!(map.len(), 1);
+}
@@ -1,2 +1,13 @@
+mod adaptive;
+mod arc_locked;
+mod bounded;
+mod comparator;
+mod dynamic;
+mod generic;
+mod indexed;
 mod locked;
+mod multi;
+mod ordered;
 mod rcu;
+mod ttl;
+mod versioned;
@@ -0,0 +1,34 @@
+use alloc::vec::Vec;
+
+use super::super::multi::LockedMultiMap;
+
+#[test]
+fn test_multimap_insert_and_remove() {
+    let map = LockedMultiMap::<&str, i32>::new();
+    map.insert("a", 1);
+    map.insert("a", 2);
+    map.insert("a", 1);
+    map.insert("b", 10);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.count("a"), 3);
+
+    let values = map.get_all("a").into_iter().map(|v| *v).collect::<Vec<_>>();
+    assert_eq!(values, [1, 2, 1]);
+    assert!(map.get_all("missing").is_empty());
+
+    // Only the first occurrence is removed
+    assert!(map.remove_one("a", &1));
+    assert!(!map.remove_one("a", &7));
+    assert!(!map.remove_one("missing", &1));
+    let values = map.get_all("a").into_iter().map(|v| *v).collect::<Vec<_>>();
+    assert_eq!(values, [2, 1]);
+
+    // Removing the last value removes the key
+    assert!(map.remove_one("b", &10));
+    assert!(!map.contains_key("b"));
+    assert_eq!(map.len(), 1);
+
+    assert_eq!(map.remove_all("a"), [2, 1]);
+    assert!(map.remove_all("a").is_empty());
+    assert!(map.is_empty());
+}
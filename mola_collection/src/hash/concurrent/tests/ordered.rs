@@ -0,0 +1,103 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use super::super::ordered::{InsertionOrderedLockedMap, RecencyLockedMap};
+
+#[test]
+fn test_ordered_iterates_in_insertion_order() {
+    let map = InsertionOrderedLockedMap::<i32, i32>::new();
+    let keys = [42, 7, 19, 3, 88, 0];
+    for key in keys {
+        assert_eq!(map.insert(key, key * 10), None);
+    }
+    let order = map.iter().map(|(k, _)| k).collect::<Vec<_>>();
+    assert_eq!(order, keys);
+
+    // Replacing a value keeps the position of the key
+    assert_eq!(map.insert(19, 191), Some(190));
+    assert_eq!(map.get(&19), Some(191));
+    let entries = map.iter().collect::<Vec<_>>();
+    assert_eq!(entries[2], (19, 191));
+
+    // Removal unlinks the head, the tail and a middle entry
+    assert_eq!(map.remove(&42), Some(420));
+    assert_eq!(map.remove(&0), Some(0));
+    assert_eq!(map.remove(&3), Some(30));
+    assert_eq!(map.remove(&3), None);
+    map.insert(5, 50);
+
+    let order = map.iter().map(|(k, _)| k).collect::<Vec<_>>();
+    assert_eq!(order, [7, 19, 88, 5]);
+    assert_eq!(map.len(), 4);
+}
+
+#[test]
+fn test_ordered_clear_and_drop_free_nodes() {
+    let map = InsertionOrderedLockedMap::<String, Vec<i32>>::new();
+    for i in 0..16 {
+        map.insert(format!("key{i}"), vec![i; 4]);
+    }
+    assert_eq!(map.remove("key3"), Some(vec![3; 4]));
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.iter().count(), 0);
+
+    // The cleared list accepts new nodes, which are freed with the map
+    map.insert("a".to_string(), vec![1]);
+    map.insert("b".to_string(), vec![2]);
+    let order = map.iter().map(|(k, _)| k).collect::<Vec<_>>();
+    assert_eq!(order, ["a", "b"]);
+}
+
+#[test]
+fn test_recency_touch_moves_to_front() {
+    let map = RecencyLockedMap::<i32, i32>::new();
+    for key in [1, 2, 3, 4] {
+        map.insert(key, key * 10);
+    }
+    assert_eq!(map.keys_by_recency(), [4, 3, 2, 1]);
+    assert_eq!(map.lru_key(), Some(1));
+
+    // Touching the back, a middle key and the front
+    assert!(map.touch(&1));
+    assert!(map.touch(&3));
+    assert!(map.touch(&3));
+    assert!(!map.touch(&9));
+    assert_eq!(map.keys_by_recency(), [3, 1, 4, 2]);
+
+    // Lookups do not count as uses, inserting an existing key does
+    assert_eq!(map.get(&2), Some(20));
+    assert_eq!(map.insert(4, 41), Some(40));
+    assert_eq!(map.keys_by_recency(), [4, 3, 1, 2]);
+
+    // The victim is always the least recently touched key
+    assert_eq!(map.pop_lru(), Some((2, 20)));
+    assert_eq!(map.lru_key(), Some(1));
+    assert_eq!(map.remove(&1), Some(10));
+    assert_eq!(map.pop_lru(), Some((3, 30)));
+    assert_eq!(map.pop_lru(), Some((4, 41)));
+    assert_eq!(map.pop_lru(), None);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_recency_clear_and_drop_free_nodes() {
+    let map = RecencyLockedMap::<String, Vec<i32>>::new();
+    for i in 0..16 {
+        map.insert(format!("key{i}"), vec![i; 4]);
+    }
+    assert!(map.touch("key0"));
+    assert_eq!(map.pop_lru(), Some(("key1".to_string(), vec![1; 4])));
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.lru_key(), None);
+    assert!(map.keys_by_recency().is_empty());
+
+    // The cleared list accepts new nodes, which are freed with the map
+    map.insert("a".to_string(), vec![1]);
+    map.insert("b".to_string(), vec![2]);
+    assert_eq!(map.keys_by_recency(), ["b", "a"]);
+}
@@ -1,11 +1,12 @@
 extern crate std;
 use alloc::{
+    borrow::Cow,
     format,
     string::{String, ToString},
     sync::Arc,
     vec::Vec,
 };
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use crossbeam_utils::thread as scoped_thread;
 use rand::{rng, seq::SliceRandom, Rng};
 use std::sync::Barrier;
@@ -13,7 +14,7 @@ use std::thread;
 use std::vec;
 
 use super::super::prelude::*;
-use super::super::rcu::HamtMap;
+use super::super::rcu::{HamtMap, RcuStorage};
 
 #[test]
 fn test_rcu_len_and_is_empty() {
@@ -313,3 +314,781 @@ fn test_get_mut() {
     let success = map.alter("nonexistent", |v| *v = 0);
     assert!(success.is_none());
 }
+
+#[test]
+fn test_try_view_and_try_alter() {
+    let map = HamtMap::<String, i32>::new();
+    map.insert("key".to_string(), 10);
+
+    let parsed = map.try_view("key", |_, v| u8::try_from(*v));
+    assert_eq!(parsed, Some(Ok(10)));
+    let failed = map.try_view("key", |_, v| if *v > 5 { Err(*v) } else { Ok(()) });
+    assert_eq!(failed, Some(Err(10)));
+    assert_eq!(map.try_view("missing", |_, v| Ok::<_, ()>(*v)), None);
+
+    // A failing closure scribbles on the working copy, which is never published
+    let before = map.get("key").unwrap().try_shared().unwrap();
+    let result = map.try_alter("key", |v| {
+        *v = -1;
+        Err::<(), _>("rejected")
+    });
+    assert_eq!(result, Some(Err("rejected")));
+    assert_eq!(map.view("key", |_, v| *v), Some(10));
+    let after = map.get("key").unwrap().try_shared().unwrap();
+    assert!(Arc::ptr_eq(&before, &after));
+
+    let result = map.try_alter("key", |v| {
+        *v += 1;
+        Ok::<_, ()>(*v)
+    });
+    assert_eq!(result, Some(Ok(11)));
+    assert_eq!(map.view("key", |_, v| *v), Some(11));
+    assert_eq!(map.try_alter("missing", |_| Ok::<_, ()>(())), None);
+}
+
+#[test]
+fn test_guard_into_inner() {
+    use crate::hash::concurrent::traits::{MutableGuard, MutableMap};
+
+    let map = HamtMap::<String, Vec<i32>>::new();
+    map.insert("key".to_string(), vec![1, 2]);
+
+    let mut guard = map.get_mut("key").unwrap();
+    guard.push(3);
+    let taken = guard.into_inner();
+    assert_eq!(taken, vec![1, 2, 3]);
+    assert_eq!(map.view("key", |_, v| v.clone()), Some(vec![1, 2]));
+
+    // The taken value can be inserted elsewhere
+    map.insert("other".to_string(), taken);
+    assert_eq!(map.view("other", |_, v| v.len()), Some(3));
+}
+
+#[test]
+fn test_fork() {
+    let map: HamtMap<i32, String> = HamtMap::new();
+    for i in 0..10 {
+        map.insert(i, format!("value_{i}"));
+    }
+
+    let fork = map.fork();
+    assert_eq!(fork.len(), 10);
+
+    // Freshly forked maps share the same value allocations
+    let original = map.get(&1).unwrap().try_shared().unwrap();
+    let forked = fork.get(&1).unwrap().try_shared().unwrap();
+    assert!(Arc::ptr_eq(&original, &forked));
+
+    // Mutating the fork leaves the original untouched
+    fork.insert(1, "changed".to_string());
+    fork.remove(&2);
+    fork.insert(100, "new".to_string());
+
+    assert_eq!(map.len(), 10);
+    assert_eq!(map.get(&1).unwrap().as_str(), "value_1");
+    assert!(map.contains_key(&2));
+    assert!(!map.contains_key(&100));
+
+    assert_eq!(fork.len(), 10);
+    assert_eq!(fork.get(&1).unwrap().as_str(), "changed");
+    assert!(!fork.contains_key(&2));
+
+    // Mutating the original leaves the fork untouched
+    map.insert(3, "original".to_string());
+    assert_eq!(fork.get(&3).unwrap().as_str(), "value_3");
+}
+
+#[test]
+fn test_fork_len_matches_snapshot_under_writes() {
+    let map: Arc<HamtMap<i32, i32>> = Arc::new(HamtMap::new());
+    let done = Arc::new(AtomicBool::new(false));
+
+    let writers: Vec<_> = (0..4)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                let mut i = 0;
+                while !done.load(Ordering::Relaxed) {
+                    let key = t * 100_000 + i;
+                    map.insert(key, i);
+                    if i % 3 == 0 {
+                        map.remove(&key);
+                    }
+                    i += 1;
+                }
+            })
+        })
+        .collect();
+
+    // The count of a fork is the number of entries in its snapshots
+    for _ in 0..100 {
+        let fork = map.fork();
+        assert_eq!(fork.len(), fork.entries_owned().count());
+    }
+
+    done.store(true, Ordering::Relaxed);
+    for writer in writers {
+        writer.join().unwrap();
+    }
+}
+
+#[test]
+fn test_replace_all() {
+    let map = HamtMap::<i32, String>::new();
+    for i in 0..20 {
+        let value = if i % 4 == 0 { "old" } else { "other" };
+        map.insert(i, value.to_string());
+    }
+
+    let replaced = map.replace_all(&"old".to_string(), "new".to_string());
+    assert_eq!(replaced, 5);
+    assert_eq!(map.len(), 20);
+
+    for i in 0..20 {
+        let expected = if i % 4 == 0 { "new" } else { "other" };
+        assert_eq!(map.view(&i, |_, v| v.clone()).unwrap(), expected);
+    }
+
+    // Nothing left to replace
+    assert_eq!(map.replace_all(&"old".to_string(), "new".to_string()), 0);
+}
+
+#[test]
+fn test_remove_prefix() {
+    let map = HamtMap::<String, i32>::new();
+    map.insert("a/1".to_string(), 1);
+    map.insert("a/2".to_string(), 2);
+    map.insert("b/1".to_string(), 3);
+
+    assert_eq!(map.remove_prefix("a/"), 2);
+    assert_eq!(map.len(), 1);
+    assert!(!map.contains_key("a/1"));
+    assert!(!map.contains_key("a/2"));
+    assert_eq!(map.view("b/1", |_, v| *v), Some(3));
+
+    // Nothing left to remove
+    assert_eq!(map.remove_prefix("a/"), 0);
+}
+
+#[test]
+fn test_increment_and_check() {
+    let map = HamtMap::<&str, u64>::new();
+    let crossings = AtomicUsize::new(0);
+
+    thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    if map.increment_and_check("hits", 1, 5000) {
+                        crossings.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(crossings.load(Ordering::Relaxed), 1);
+    assert_eq!(map.view("hits", |_, v| *v), Some(8000));
+
+    // A single jump over the threshold counts as a crossing
+    assert!(map.increment_and_check("other", 10, 5));
+    assert!(!map.increment_and_check("other", 10, 5));
+}
+
+#[test]
+fn test_retain_and_remove_keys_in() {
+    use hashbrown::HashSet;
+
+    let map = HamtMap::<i32, i32>::new();
+    for i in 0..100 {
+        map.insert(i, i);
+    }
+
+    let keep = (0..30).map(|i| i * 3).collect::<HashSet<i32>>();
+    assert_eq!(map.retain_keys_in(&keep), 70);
+    assert_eq!(map.len(), 30);
+    for i in 0..100 {
+        assert_eq!(map.contains_key(&i), keep.contains(&i));
+    }
+
+    let drop = (0..10).collect::<HashSet<i32>>();
+    assert_eq!(map.remove_keys_in(&drop), 4);
+    assert_eq!(map.len(), 26);
+    assert!(!map.contains_key(&9));
+    assert!(map.contains_key(&12));
+}
+
+#[test]
+fn test_get_with_shard_len() {
+    let map = HamtMap::<i32, i32>::new();
+    for i in 0..100 {
+        map.insert(i, i * 10);
+    }
+
+    for i in 0..100 {
+        let (value, shard_len) = map.get_with_shard_len(&i).unwrap();
+        assert_eq!(*value, i * 10);
+        let index = map.shard_index_for_key(&i);
+        assert_eq!(shard_len, map.iter_shard_range(index, index + 1).count());
+    }
+    assert!(map.get_with_shard_len(&100).is_none());
+}
+
+#[test]
+fn test_single_shard() {
+    let map: HamtMap<i32, i32> =
+        HamtMap::with_shards_and_hasher(1, hashbrown::DefaultHashBuilder::default());
+    assert_eq!(map.shard_count(), 1);
+    assert!(!map.storage.is_cache_padded());
+
+    for i in 0..100 {
+        map.insert(i, i * 2);
+    }
+    assert_eq!(map.len(), 100);
+    for i in 0..100 {
+        assert_eq!(*map.get(&i).unwrap(), i * 2);
+    }
+    for i in 0..50 {
+        assert!(map.remove(&i).is_some());
+    }
+    assert_eq!(map.len(), 50);
+    assert!(!map.contains_key(&0));
+    assert!(map.contains_key(&99));
+}
+
+#[test]
+fn test_remove_entry() {
+    let map: HamtMap<String, i32> = HamtMap::new();
+    map.insert("key".to_string(), 42);
+    map.insert("other".to_string(), 7);
+
+    assert_eq!(map.remove_entry("key"), Some(("key".to_string(), 42)));
+    assert_eq!(map.remove_entry("key"), None);
+    assert!(!map.contains_key("key"));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_insert_or_get_race() {
+    let map: Arc<HamtMap<i32, usize>> = Arc::new(HamtMap::new());
+    let num_threads = 8;
+
+    let mut handles = vec![];
+    for i in 0..num_threads {
+        let map_clone = Arc::clone(&map);
+        handles.push(thread::spawn(move || *map_clone.insert_or_get(1, i)));
+    }
+    let observed: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // Every thread observes the single winning value
+    let winner = observed[0];
+    assert!(observed.iter().all(|&v| v == winner));
+    assert_eq!(*map.get(&1).unwrap(), winner);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_get_or_init_arc_race() {
+    let map = HamtMap::<&str, String>::new();
+    let barrier = Barrier::new(8);
+    let inits = AtomicUsize::new(0);
+
+    let observed: Vec<Arc<String>> = thread::scope(|s| {
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let (map, barrier, inits) = (&map, &barrier, &inits);
+                s.spawn(move || {
+                    barrier.wait();
+                    map.get_or_init_arc("config", || {
+                        inits.fetch_add(1, Ordering::Relaxed);
+                        format!("tenant-{i}")
+                    })
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    // Every caller shares the single stored instance
+    let stored = map.get("config").unwrap().try_shared().unwrap();
+    assert!(observed.iter().all(|arc| Arc::ptr_eq(arc, &stored)));
+    assert!((1..=8).contains(&inits.load(Ordering::Relaxed)));
+    assert_eq!(map.len(), 1);
+
+    // Later calls return the stored instance without initializing
+    let again = map.get_or_init_arc("config", || unreachable!());
+    assert!(Arc::ptr_eq(&again, &stored));
+}
+
+#[test]
+fn test_contains_all_and_any() {
+    let map = HamtMap::<i32, i32>::new();
+    for i in 0..50 {
+        map.insert(i, i);
+    }
+
+    let present: Vec<&i32> = [1, 7, 23, 49].iter().collect();
+    let some_missing: Vec<&i32> = [1, 7, 100, 49].iter().collect();
+    let none_present: Vec<&i32> = [100, 200, 300].iter().collect();
+
+    assert!(map.contains_all(&present));
+    assert!(map.contains_any(&present));
+
+    assert!(!map.contains_all(&some_missing));
+    assert!(map.contains_any(&some_missing));
+
+    assert!(!map.contains_all(&none_present));
+    assert!(!map.contains_any(&none_present));
+
+    // Empty key sets behave like the empty quantifiers
+    assert!(map.contains_all(&[]));
+    assert!(!map.contains_any(&[]));
+}
+
+#[test]
+fn test_fetch_update_under_contention() {
+    let map: Arc<HamtMap<i32, usize>> = Arc::new(HamtMap::new());
+    map.insert(1, 0);
+    let num_threads = 8;
+    let increments = 500;
+
+    let handles: Vec<_> = (0..num_threads)
+        .map(|_| {
+            let map_clone = Arc::clone(&map);
+            thread::spawn(move || {
+                for _ in 0..increments {
+                    map_clone.fetch_update(&1, |v| Some(v + 1)).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // No update was lost
+    assert_eq!(*map.get(&1).unwrap(), num_threads * increments);
+
+    // Aborting leaves the value untouched
+    assert!(map.fetch_update(&1, |_| None).is_err());
+    assert!(map.fetch_update(&2, |v| Some(*v)).is_err());
+    assert_eq!(*map.fetch_update(&1, |v| Some(v * 2)).unwrap(), num_threads * increments * 2);
+}
+
+#[test]
+fn test_compact_versions_drops_old_versions() {
+    let map: HamtMap<i32, usize> = HamtMap::new();
+    map.insert(1, 0);
+    let shard = map.shard_for_key(&1);
+    let first_table = shard.table.load_full();
+    let first_value = map.get(&1).unwrap().try_shared().unwrap();
+
+    for i in 1..1000 {
+        let guard = shard.table.load();
+        map.insert(1, i);
+        assert_eq!(*map.get(&1).unwrap(), i);
+        drop(guard);
+    }
+    map.compact_versions();
+
+    // Only this test still holds the first version and its value
+    assert_eq!(Arc::strong_count(&first_table), 1);
+    assert_eq!(Arc::strong_count(&first_value), 1);
+
+    // The current version is held by the shard and this test only
+    let current = shard.table.load_full();
+    assert_eq!(Arc::strong_count(&current), 2);
+    assert_eq!(*map.get(&1).unwrap(), 999);
+}
+
+#[test]
+fn test_rename() {
+    let map: HamtMap<i32, i32> =
+        HamtMap::with_shards_and_hasher(1, hashbrown::DefaultHashBuilder::default());
+    map.insert(1, 10);
+    map.insert(2, 20);
+
+    assert!(map.rename(&1, 3));
+    assert!(!map.contains_key(&1));
+    assert_eq!(*map.get(&3).unwrap(), 10);
+    assert_eq!(map.len(), 2);
+
+    // Renaming onto an existing key replaces its value
+    assert!(map.rename(&3, 2));
+    assert_eq!(*map.get(&2).unwrap(), 10);
+    assert_eq!(map.len(), 1);
+    assert!(!map.rename(&1, 4));
+
+    // Across shards
+    let map = HamtMap::<i32, i32>::new();
+    map.insert(0, 1);
+    let other = (1..)
+        .find(|k| !core::ptr::eq(map.shard_for_key(k), map.shard_for_key(&0)))
+        .unwrap();
+    assert!(map.rename(&0, other));
+    assert_eq!(*map.get(&other).unwrap(), 1);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_replace_with() {
+    let map: HamtMap<i32, i32> = HamtMap::new();
+    map.insert(1, 10);
+
+    let (old, new) = map.replace_with(&1, |v| v * 2).unwrap();
+    assert_eq!((*old, *new), (10, 20));
+    assert_eq!(*map.get(&1).unwrap(), 20);
+
+    assert!(map.replace_with(&2, |v| v + 1).is_none());
+    assert!(!map.contains_key(&2));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_swap_value() {
+    let map: HamtMap<i32, i32> = HamtMap::new();
+    map.insert(1, 10);
+
+    assert_eq!(*map.swap_value(&1, 11).unwrap(), 10);
+    assert_eq!(*map.get(&1).unwrap(), 11);
+
+    assert_eq!(map.swap_value(&2, 20).err(), Some(20));
+    assert!(!map.contains_key(&2));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_insert_if_absent_race() {
+    let map: Arc<HamtMap<i32, usize>> = Arc::new(HamtMap::new());
+    let num_threads = 8;
+
+    let mut handles = vec![];
+    for i in 0..num_threads {
+        let map_clone = Arc::clone(&map);
+        handles.push(thread::spawn(move || (i, map_clone.insert_if_absent(1, i))));
+    }
+    let results: Vec<(usize, bool)> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // Exactly one thread added the key, and its value was kept
+    let winners: Vec<usize> = results
+        .iter()
+        .filter(|(_, added)| *added)
+        .map(|(i, _)| *i)
+        .collect();
+    assert_eq!(winners.len(), 1);
+    assert_eq!(map.view(&1, |_, v| *v), Some(winners[0]));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_raw_count_converges() {
+    let map: Arc<HamtMap<i32, i32>> = Arc::new(HamtMap::new());
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                for i in 0..100 {
+                    map.insert(t * 100 + i, i);
+                }
+                for i in 0..50 {
+                    map.remove(&(t * 100 + i));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(map.raw_count(), map.len());
+    assert_eq!(map.raw_count(), 200);
+}
+
+#[test]
+fn test_transact_conserves_total() {
+    let map: Arc<HamtMap<i32, i64>> = Arc::new(HamtMap::with_shards_and_hasher(
+        1,
+        hashbrown::DefaultHashBuilder::default(),
+    ));
+    map.insert(1, 1000);
+    map.insert(2, 1000);
+
+    let handles: Vec<_> = (0..8)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                for _ in 0..200 {
+                    let keys = if t % 2 == 0 { [1, 2] } else { [2, 1] };
+                    assert!(map.transact(keys, |[from, to]| {
+                        *from -= 3;
+                        *to += 3;
+                    }));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // Both directions moved the same amount, so the values are back to the start
+    assert_eq!(*map.get(&1).unwrap() + *map.get(&2).unwrap(), 2000);
+    assert_eq!(*map.get(&1).unwrap(), 1000);
+    assert!(!map.transact([1, 3], |_| {}));
+}
+
+#[test]
+fn test_transact_conserves_total_across_shards() {
+    let map: Arc<HamtMap<i32, i64>> = Arc::new(HamtMap::new());
+    for account in 0..8 {
+        map.insert(account, 1000);
+    }
+    let done = Arc::new(AtomicBool::new(false));
+
+    // Plain writers on other keys make the shard CAS of transactions fail
+    let writers: Vec<_> = (0..2)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                let mut i = 0;
+                while !done.load(Ordering::Relaxed) {
+                    map.insert(100 + t * 1000 + i % 500, i as i64);
+                    i += 1;
+                }
+            })
+        })
+        .collect();
+
+    let handles: Vec<_> = (0..8)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                let mut rng = rng();
+                for _ in 0..300 {
+                    let from = rng.random_range(0..8);
+                    let to = (from + rng.random_range(1..8)) % 8;
+                    let amount = t + 1;
+                    assert!(map.transact([from, to], |[from, to]| {
+                        *from -= amount;
+                        *to += amount;
+                    }));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    done.store(true, Ordering::Relaxed);
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let total: i64 = (0..8).map(|account| *map.get(&account).unwrap()).sum();
+    assert_eq!(total, 8000);
+}
+
+#[test]
+fn test_get_cow_is_owned() {
+    let map: HamtMap<i32, String> = HamtMap::new();
+    map.insert(1, "one".to_string());
+
+    let cow = map.get_cow(&1).unwrap();
+    assert!(matches!(&cow, Cow::Owned(value) if value == "one"));
+
+    // The owned copy is independent of the table
+    map.insert(1, "uno".to_string());
+    assert_eq!(cow.as_str(), "one");
+    assert_eq!(map.get_cow(&1).unwrap().as_str(), "uno");
+    assert_eq!(map.get_cow(&2), None);
+}
+
+#[test]
+fn test_remove_owned() {
+    let map: HamtMap<i32, String> = HamtMap::new();
+    map.insert(1, "one".to_string());
+    map.insert(2, "two".to_string());
+
+    assert_eq!(map.remove_owned(&1), Some(Ok("one".to_string())));
+
+    // A value still held by a reader cannot be unwrapped
+    let held = map.get(&2).unwrap();
+    let shared = map.remove_owned(&2).unwrap().unwrap_err();
+    assert_eq!(*shared, "two");
+    assert_eq!(Arc::strong_count(&shared), 2);
+    drop(held);
+
+    assert!(map.remove_owned(&3).is_none());
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_iter_shard_range() {
+    let map: HamtMap<i32, i32> =
+        HamtMap::with_shards_and_hasher(8, hashbrown::DefaultHashBuilder::default());
+    for i in 0..500 {
+        map.insert(i, i * 2);
+    }
+
+    // Two workers splitting the shards see every entry exactly once
+    let mut seen: Vec<(i32, i32)> = map
+        .iter_shard_range(0, 5)
+        .chain(map.iter_shard_range(5, 8))
+        .map(|(k, v)| (k, *v))
+        .collect();
+    seen.sort_unstable();
+    assert_eq!(seen, (0..500).map(|i| (i, i * 2)).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_upsert() {
+    let map: Arc<HamtMap<i32, Vec<i32>>> = Arc::new(HamtMap::new());
+
+    // A new key takes the value without running the closure
+    assert!(map.upsert(1, vec![1], |_| panic!("Key 1 must be absent")));
+    assert!(!map.upsert(1, vec![0], |v| v.push(2)));
+    assert_eq!(map.view(&1, |_, v| v.clone()), Some(vec![1, 2]));
+    assert_eq!(map.len(), 1);
+
+    // Concurrent upserts insert once and modify the rest of the time
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || map.upsert(2, vec![i], |v| v.push(i)))
+        })
+        .collect();
+    let inserted = handles
+        .into_iter()
+        .map(|h| h.join().unwrap())
+        .filter(|inserted| *inserted)
+        .count();
+    assert_eq!(inserted, 1);
+    assert_eq!(map.view(&2, |_, v| v.len()), Some(8));
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_append_to_entry() {
+    let map: Arc<HamtMap<i32, Vec<i32>>> = Arc::new(HamtMap::new());
+    map.append_to_entry(1, [1, 2]);
+    map.append_to_entry(1, Vec::<i32>::new());
+    map.append_to_entry(1, vec![3]);
+    assert_eq!(map.view(&1, |_, v| v.clone()), Some(vec![1, 2, 3]));
+    assert_eq!(map.len(), 1);
+
+    // Concurrent appends to the same key keep every item
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                for j in 0..100 {
+                    map.append_to_entry(2, [i * 100 + j]);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let mut items = map.view(&2, |_, v| v.clone()).unwrap();
+    items.sort_unstable();
+    assert_eq!(items, (0..800).collect::<Vec<_>>());
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_unpadded_shards() {
+    let map: HamtMap<i32, i32> = ConcurrentMap::with_storage_and_hasher(
+        RcuStorage::with_shards_and_padding(64, false),
+        hashbrown::DefaultHashBuilder::default(),
+    );
+    let padded = RcuStorage::<i32, i32>::with_shards(64);
+    assert!(!map.storage.is_cache_padded());
+    assert!(!padded.fork().with_cache_padding(false).is_cache_padded());
+    assert!(map.storage.shards_size_in_bytes() < padded.shards_size_in_bytes());
+
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    assert_eq!(map.remove_entry(&3), Some((3, 3)));
+    assert_eq!(*map.get(&999).unwrap(), 999);
+    assert_eq!(map.len(), 999);
+    assert!(!map.storage.fork().is_cache_padded());
+}
+
+#[test]
+fn test_seeded_hasher_iteration_order() {
+    let keys = (0..1000).map(|i| i * 7919 % 1000).collect::<Vec<i32>>();
+    let order = |seed| {
+        let map: HamtMap<i32, i32, _> = HamtMap::with_seeded_hasher(seed);
+        for &key in &keys {
+            map.insert(key, key);
+        }
+        map.iter_shard_range(0, map.shard_count())
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(order(42), order(42));
+    assert_eq!(order(42).len(), 1000);
+    assert_ne!(order(42), order(43));
+}
+
+#[test]
+fn test_swap_contents() {
+    let blue: HamtMap<i32, i32, _> = HamtMap::with_seeded_hasher(7);
+    let green: HamtMap<i32, i32, _> = HamtMap::with_seeded_hasher(7);
+    for i in 0..100 {
+        blue.insert(i, i);
+    }
+    for i in 100..150 {
+        green.insert(i, -i);
+    }
+
+    blue.swap_contents(&green);
+
+    assert_eq!(blue.len(), 50);
+    assert_eq!(green.len(), 100);
+    for i in 0..100 {
+        assert!(!blue.contains_key(&i));
+        assert_eq!(*green.get(&i).unwrap(), i);
+    }
+    for i in 100..150 {
+        assert!(!green.contains_key(&i));
+        assert_eq!(*blue.get(&i).unwrap(), -i);
+    }
+
+    // Swapping a map with itself leaves it unchanged
+    blue.swap_contents(&blue);
+    assert_eq!(blue.len(), 50);
+}
+
+#[test]
+fn test_get_or_try_insert_with_race() {
+    let map: Arc<HamtMap<i32, usize>> = Arc::new(HamtMap::new());
+
+    // Odd threads compute a value, even threads fail
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                let result = map
+                    .get_or_try_insert_with(1, || if i % 2 == 1 { Ok(i) } else { Err(i) })
+                    .map(|v| *v);
+                let failed = map.get_or_try_insert_with(2, || Err::<usize, _>(i));
+                assert_eq!(failed.err(), Some(i));
+                result
+            })
+        })
+        .collect();
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // Successes converge to the stored value, failures inserted nothing
+    let winner = map.view(&1, |_, v| *v).unwrap();
+    assert_eq!(winner % 2, 1);
+    assert!(results.iter().all(|r| *r == Ok(winner) || r.is_err()));
+    assert!(results.contains(&Ok(winner)));
+    assert!(!map.contains_key(&2));
+    assert_eq!(map.len(), 1);
+}
@@ -0,0 +1,70 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+use super::super::ttl::{Clock, TtlLockedMap};
+
+/// A clock that only moves when advanced by the test.
+#[derive(Default)]
+struct ManualClock {
+    millis: AtomicU64,
+}
+
+impl ManualClock {
+    fn advance(&self, millis: u64) {
+        self.millis.fetch_add(millis, Ordering::Relaxed);
+    }
+}
+
+impl Clock for &ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.millis.load(Ordering::Relaxed))
+    }
+}
+
+#[test]
+fn test_ttl_expires_lazily() {
+    let clock = ManualClock::default();
+    let map = TtlLockedMap::<i32, i32, _>::with_clock(Duration::from_millis(100), &clock);
+    map.insert(1, 10);
+    map.insert_with_ttl(2, 20, Duration::from_millis(300));
+
+    clock.advance(99);
+    assert_eq!(map.get(&1), Some(10));
+
+    // Key 1 reached its deadline and is reaped by the lookup
+    clock.advance(1);
+    assert_eq!(map.get(&1), None);
+    assert!(!map.contains_key(&1));
+    assert_eq!(map.len(), 1);
+    assert!(map.contains_key(&2));
+
+    // Replacing an expired entry does not return the stale value
+    map.insert(3, 30);
+    clock.advance(100);
+    assert_eq!(map.insert(3, 31), None);
+    assert_eq!(map.insert(3, 32), Some(31));
+}
+
+#[test]
+fn test_ttl_sweep() {
+    let clock = ManualClock::default();
+    let map = TtlLockedMap::<i32, i32, _>::with_clock(Duration::from_millis(50), &clock);
+    for i in 0..100 {
+        map.insert(i, i);
+    }
+    clock.advance(25);
+    for i in 100..110 {
+        map.insert(i, i);
+    }
+
+    assert_eq!(map.sweep(), 0);
+    clock.advance(25);
+    assert_eq!(map.sweep(), 100);
+    assert_eq!(map.len(), 10);
+    assert_eq!(map.remove(&105), Some(105));
+
+    clock.advance(25);
+    assert_eq!(map.remove(&106), None);
+    assert_eq!(map.sweep(), 8);
+    assert!(map.is_empty());
+}
@@ -0,0 +1,47 @@
+use alloc::string::{String, ToString};
+
+use super::super::versioned::{CasError, VersionedLockedMap};
+
+struct Doc {
+    version: u64,
+    body: String,
+}
+
+#[test]
+fn test_cas_version() {
+    let docs = VersionedLockedMap::new(|doc: &mut Doc| &mut doc.version);
+    docs.insert(
+        "readme",
+        Doc {
+            version: 0,
+            body: "draft".to_string(),
+        },
+    );
+
+    // Two writers read version 0, only the first update goes through
+    let read = docs.view("readme", |_, doc| doc.version).unwrap();
+    assert_eq!(
+        docs.cas_version("readme", read, |doc| doc.body.push_str(" one")),
+        Ok(())
+    );
+    assert_eq!(
+        docs.cas_version("readme", read, |doc| doc.body.push_str(" two")),
+        Err(CasError::VersionMismatch { current: 1 })
+    );
+    assert_eq!(
+        docs.view("readme", |_, doc| (doc.version, doc.body.clone())),
+        Some((1, "draft one".to_string()))
+    );
+
+    // Retrying against the current version succeeds
+    assert_eq!(
+        docs.cas_version("readme", 1, |doc| doc.body.push_str(" two")),
+        Ok(())
+    );
+    assert_eq!(docs.view("readme", |_, doc| doc.version), Some(2));
+    assert_eq!(
+        docs.cas_version("missing", 0, |_| unreachable!()),
+        Err(CasError::KeyNotFound)
+    );
+    assert_eq!(docs.len(), 1);
+}
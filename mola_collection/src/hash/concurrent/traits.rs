@@ -3,7 +3,6 @@ use core::hash::Hash;
 use core::ops::{Deref, DerefMut};
 
 use alloc::sync::Arc;
-use crossbeam_utils::CachePadded;
 
 use crate::hash::concurrent::wrapper::MaybeArc;
 
@@ -17,12 +16,15 @@ pub trait ShardStorage<K, V>: Send + Sync {
 
     /// Get the shard that should contain the given hash value
     ///
+    /// Returns the shard itself, whether or not the storage pads it to a
+    /// cache line.
+    ///
     /// # Arguments
     /// * `hash` - The hash value to determine the shard for
     ///
     /// # Returns
     /// A reference to the appropriate shard
-    fn shard_for_hash(&self, hash: u64) -> &CachePadded<Self::Shard>;
+    fn shard_for_hash(&self, hash: u64) -> &Self::Shard;
 
     /// Get the total number of shards
     ///
@@ -118,6 +120,23 @@ where
     where
         K: Borrow<Q>,
         Q: ?Sized + Eq + Hash;
+
+    /// Get a value from the hash map, turning a miss into an error.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `err` - A closure creating the error if the key does not exist
+    ///
+    /// # Returns
+    /// A reference to the value, or the error if the key does not exist
+    fn get_or_err<Q, E, F>(&self, key: &Q, err: F) -> Result<MaybeArc<V>, E>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce() -> E,
+    {
+        self.get(key).ok_or_else(err)
+    }
 }
 
 /// A trait for read-only view operations on concurrent hash maps.
@@ -131,6 +150,10 @@ where
 
     /// Perform a read-only view operation on a key-value pair.
     ///
+    /// Unlike [`ReadableMap::get`], this does not require `V: Clone`, so it
+    /// can be used to access values that cannot be cloned, such as
+    /// `Box<dyn Fn() -> i32 + Send + Sync>`.
+    ///
     /// # Arguments
     /// * `key` - The key to look up
     /// * `f` - A closure that receives the key and value references
@@ -142,6 +165,23 @@ where
         K: Borrow<Q>,
         Q: ?Sized + Eq + Hash,
         F: FnOnce(&K, &V) -> R;
+
+    /// Perform a fallible read-only view operation on a key-value pair.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A fallible closure that receives the key and value references
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    fn try_view<Q, F, R, E>(&self, key: &Q, f: F) -> Option<Result<R, E>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&K, &V) -> Result<R, E>,
+    {
+        self.view(key, f)
+    }
 }
 
 /// A trait for mutable guards in concurrent hash maps.
@@ -161,6 +201,20 @@ where
     /// Ok if the commit was successful, Err if there was an issue
     /// committing the changes.
     fn commit(self) -> Result<(), ()>;
+
+    /// Take the staged value out of the guard without committing it.
+    ///
+    /// The map is left unchanged. The default clones the value through the
+    /// guard, guards owning their staged value should move it out instead.
+    ///
+    /// # Returns
+    /// The value, including the changes made through the guard
+    fn into_inner(self) -> V
+    where
+        V: Clone,
+    {
+        V::clone(&self)
+    }
 }
 
 /// A trait for get mutable reference on concurrent hash maps.
@@ -208,6 +262,28 @@ where
         Q: ?Sized + Eq + Hash,
         F: FnOnce(&mut V) -> R;
 
+    /// Modify an existing entry in place with a fallible closure.
+    ///
+    /// Whether changes made before the closure fails are kept depends on the
+    /// backend: the default runs the closure through [`alter`](Self::alter),
+    /// which modifies the value in place and keeps them, while the RCU map
+    /// discards its working copy without publishing it.
+    ///
+    /// # Arguments
+    /// * `key` - The key to modify
+    /// * `f` - A fallible closure that receives a mutable reference to the value
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    fn try_alter<Q, F, R, E>(&self, key: &Q, f: F) -> Option<Result<R, E>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&mut V) -> Result<R, E>,
+    {
+        self.alter(key, f)
+    }
+
     /// Atomically modify an entry if it exists, or insert a default and then modify.
     ///
     /// # Arguments
@@ -219,6 +295,158 @@ where
         F: FnOnce(&mut V),
         D: FnOnce() -> V;
 
+    /// Atomically modify an entry, or insert a default and then modify it,
+    /// retrying until the update is applied.
+    ///
+    /// Unlike `alter_entry`, the update is never lost to a concurrent
+    /// writer. Backends publishing copies of the value, like the RCU map,
+    /// retry the whole step when another thread modified the shard
+    /// meanwhile, so `default` and `f` may run several times. The default
+    /// implementation runs `alter_entry` once, which is enough for backends
+    /// modifying the value in place under a lock.
+    ///
+    /// # Arguments
+    /// * `key` - The key to operate on
+    /// * `default` - A closure to create a new value if the key is absent
+    /// * `f` - A closure to modify the existing or newly created value
+    fn update_entry<F, D>(&self, key: K, default: D, f: F)
+    where
+        F: FnMut(&mut V),
+        D: FnMut() -> V,
+    {
+        self.alter_entry(key, default, f);
+    }
+}
+
+/// A trait bundling the common operations shared by all concurrent map backends.
+///
+/// It is implemented automatically for every map implementing the bundled
+/// traits, so generic code can accept any backend with a single bound.
+pub trait ConcurrentMapExt<K, V>:
+    RawHashMap<K, V> + ReadableMap<K, V> + ReadableInPlaceMap<K, V> + MutableInPlaceMap<K, V>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+}
+
+impl<K, V, M> ConcurrentMapExt<K, V> for M
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    M: RawHashMap<K, V> + ReadableMap<K, V> + ReadableInPlaceMap<K, V> + MutableInPlaceMap<K, V>,
+{
+}
+
+/// A numeric map value that can be incremented without wrapping around.
+pub trait CounterValue: Copy + Default + Ord + Send + Sync {
+    /// Add `rhs`, clamping at the numeric bounds.
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    /// Add `rhs`, returning `None` on overflow.
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_counter_value {
+    ($($t:ty),*) => {
+        $(
+            impl CounterValue for $t {
+                #[inline]
+                fn saturating_add(self, rhs: Self) -> Self {
+                    <$t>::saturating_add(self, rhs)
+                }
+
+                #[inline]
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_counter_value!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// A trait for maps of counters that must not silently wrap around.
+///
+/// It is implemented automatically for every [`MutableInPlaceMap`] with a
+/// [`CounterValue`], and each increment runs inside a single `update_entry`,
+/// so concurrent increments are never lost. Absent keys start from the
+/// default value, i.e. zero.
+pub trait CounterMap<K, V>: MutableInPlaceMap<K, V>
+where
+    K: Hash + Eq + Send + Sync,
+    V: CounterValue,
+{
+    /// Add `delta` to the counter of the key, clamping at the numeric bounds.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the counter
+    /// * `delta` - The amount to add
+    ///
+    /// # Returns
+    /// The new value of the counter
+    fn increment_saturating(&self, key: K, delta: V) -> V {
+        let mut result = V::default();
+        self.update_entry(key, V::default, |v| {
+            *v = v.saturating_add(delta);
+            result = *v;
+        });
+        result
+    }
+
+    /// Add `delta` to the counter of the key, unless it would overflow.
+    ///
+    /// On overflow the counter is left unchanged.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the counter
+    /// * `delta` - The amount to add
+    ///
+    /// # Returns
+    /// The new value of the counter, or `None` if the addition overflowed
+    fn increment_checked(&self, key: K, delta: V) -> Option<V> {
+        let mut result = None;
+        self.update_entry(key, V::default, |v| {
+            result = v.checked_add(delta);
+            if let Some(new) = result {
+                *v = new;
+            }
+        });
+        result
+    }
+
+    /// Add `by` to the counter of the key, clamping at `cap`.
+    ///
+    /// This is the building block of rate limiters: the read, the addition
+    /// and the clamp happen in one update, so concurrent bumps are never
+    /// lost and the counter never exceeds `cap`.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the counter
+    /// * `by` - The amount to add
+    /// * `cap` - The maximum value of the counter
+    ///
+    /// # Returns
+    /// The new value of the counter
+    fn bump(&self, key: K, by: V, cap: V) -> V {
+        let mut result = V::default();
+        self.update_entry(key, V::default, |v| {
+            *v = v.saturating_add(by).min(cap);
+            result = *v;
+        });
+        result
+    }
+}
+
+impl<K, V, M> CounterMap<K, V> for M
+where
+    K: Hash + Eq + Send + Sync,
+    V: CounterValue,
+    M: MutableInPlaceMap<K, V>,
+{
 }
 
 /// A trait for concurrent hash maps that support atomic set operation.
@@ -0,0 +1,274 @@
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::time::Duration;
+
+use hashbrown::DefaultHashBuilder;
+
+use super::locked_impl::{LockedMap, DEFAULT_SHARDS};
+use super::traits::{RawHashMap, ReadableInPlaceMap, ShardStorage};
+
+/// A source of monotonic time for expiring entries.
+pub trait Clock: Send + Sync {
+    /// Get the time elapsed since an arbitrary fixed origin.
+    ///
+    /// Successive calls must never go backwards.
+    fn now(&self) -> Duration;
+}
+
+/// A clock backed by `std::time::Instant`, measuring from its creation.
+#[cfg(feature = "std")]
+pub struct InstantClock {
+    origin: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl InstantClock {
+    /// Create a new clock starting at the current instant.
+    pub fn new() -> Self {
+        Self {
+            origin: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for InstantClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for InstantClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// A locked concurrent map whose entries expire after a time-to-live.
+///
+/// Every entry stores its deadline next to its value. Expired entries are
+/// treated as absent by every lookup, and a lookup hitting one removes it.
+/// Entries that are never looked up again stay in memory until [`sweep`]
+/// is called, and are still counted by [`len`] until then.
+///
+/// [`sweep`]: TtlLockedMap::sweep
+/// [`len`]: TtlLockedMap::len
+pub struct TtlLockedMap<K, V, C, S = DefaultHashBuilder> {
+    map: LockedMap<K, (Duration, V), S>,
+    ttl: Duration,
+    clock: C,
+}
+
+#[cfg(feature = "std")]
+impl<K, V> TtlLockedMap<K, V, InstantClock>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+{
+    /// Create a new map measuring time with `std::time::Instant`.
+    ///
+    /// # Arguments
+    /// * `ttl` - The default time-to-live of inserted entries
+    ///
+    /// # Returns
+    /// A new map instance
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_clock(ttl, InstantClock::new())
+    }
+}
+
+impl<K, V, C> TtlLockedMap<K, V, C>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    C: Clock,
+{
+    /// Create a new map measuring time with the given clock.
+    ///
+    /// # Arguments
+    /// * `ttl` - The default time-to-live of inserted entries
+    /// * `clock` - The clock deciding when entries expire
+    ///
+    /// # Returns
+    /// A new map instance
+    pub fn with_clock(ttl: Duration, clock: C) -> Self {
+        Self::with_clock_and_hasher(ttl, clock, Default::default())
+    }
+}
+
+impl<K, V, C, S> TtlLockedMap<K, V, C, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    C: Clock,
+    S: BuildHasher + Send + Sync,
+{
+    /// Create a new map with custom settings.
+    ///
+    /// # Arguments
+    /// * `ttl` - The default time-to-live of inserted entries
+    /// * `clock` - The clock deciding when entries expire
+    /// * `hash_builder` - The hash builder to use
+    ///
+    /// # Returns
+    /// A new map instance
+    pub fn with_clock_and_hasher(ttl: Duration, clock: C, hash_builder: S) -> Self {
+        Self {
+            map: LockedMap::with_shards_and_capacity_and_hasher(DEFAULT_SHARDS, 0, hash_builder),
+            ttl,
+            clock,
+        }
+    }
+
+    /// Get the default time-to-live of inserted entries.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Insert a key-value pair expiring after the default time-to-live.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any and not expired
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.insert_with_ttl(key, value, self.ttl)
+    }
+
+    /// Insert a key-value pair expiring after the given time-to-live.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    /// * `ttl` - The time-to-live of this entry
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any and not expired
+    pub fn insert_with_ttl(&self, key: K, value: V, ttl: Duration) -> Option<V> {
+        let now = self.clock.now();
+        self.map
+            .insert(key, (now.saturating_add(ttl), value))
+            .and_then(|old| old.try_owned())
+            .filter(|(deadline, _)| *deadline > now)
+            .map(|(_, v)| v)
+    }
+
+    /// Remove the entry of the key if it expired at `now`.
+    fn reap<Q>(&self, key: &Q, now: Duration)
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let hash = self.map.hash_key(key);
+        let mut table = self.map.storage.shard_for_hash(hash).table.write();
+        let expired = table.find_entry(hash, |(h, k, (deadline, _))| {
+            *h == hash && k.borrow() == key && *deadline <= now
+        });
+        if let Ok(entry) = expired {
+            entry.remove();
+            self.map.storage.shard_decrement(1);
+        }
+    }
+
+    /// Run a closure on the entry associated with the key, if not expired.
+    ///
+    /// An expired entry is removed from the map.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the key and value references
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists and has not expired,
+    /// None otherwise
+    pub fn view<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&K, &V) -> R,
+    {
+        let now = self.clock.now();
+        let result = self
+            .map
+            .view(key, |k, (deadline, v)| (*deadline > now).then(|| f(k, v)))?;
+        if result.is_none() {
+            self.reap(key, now);
+        }
+        result
+    }
+
+    /// Get a clone of the value associated with the key, if not expired.
+    ///
+    /// # Returns
+    /// A clone of the value, if the key exists and has not expired
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        V: Clone,
+    {
+        self.view(key, |_, v| v.clone())
+    }
+
+    /// Check if a key exists in the map and has not expired.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.view(key, |_, _| ()).is_some()
+    }
+
+    /// Remove a key from the map.
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed and had not expired
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let now = self.clock.now();
+        self.map
+            .remove_entry(key)
+            .filter(|(_, (deadline, _))| *deadline > now)
+            .map(|(_, (_, v))| v)
+    }
+
+    /// Remove every expired entry.
+    ///
+    /// Shards are write-locked one at a time.
+    ///
+    /// # Returns
+    /// The number of entries removed
+    pub fn sweep(&self) -> usize {
+        let now = self.clock.now();
+        let mut removed = 0;
+        for shard in self.map.storage.shards.iter() {
+            let mut table = shard.table.write();
+            let before = table.len();
+            table.retain(|(_, _, (deadline, _))| *deadline > now);
+            removed += before - table.len();
+        }
+        self.map.storage.shard_decrement(removed);
+        removed
+    }
+
+    /// Get the number of entries in the map, including expired entries that
+    /// were not removed yet.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check if the map is empty, counting expired entries that were not
+    /// removed yet.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
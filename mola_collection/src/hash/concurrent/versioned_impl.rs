@@ -0,0 +1,172 @@
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::DefaultHashBuilder;
+
+use super::locked_impl::{LockedMap, DEFAULT_SHARDS};
+use super::traits::{MutableInPlaceMap, RawHashMap, ReadableInPlaceMap};
+
+/// The reason a versioned update was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CasError {
+    /// The key does not exist in the map.
+    KeyNotFound,
+    /// The stored value has moved on from the expected version.
+    VersionMismatch {
+        /// The version currently stored.
+        current: u64,
+    },
+}
+
+impl fmt::Display for CasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeyNotFound => write!(f, "key not found"),
+            Self::VersionMismatch { current } => {
+                write!(f, "version mismatch, current version is {current}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for CasError {}
+
+/// A locked concurrent map of values carrying a version number.
+///
+/// The version is reached through an accessor given at construction, such
+/// as `|v: &mut Doc| &mut v.version`. Updates made with
+/// [`cas_version`](Self::cas_version) only apply if the caller saw the
+/// latest version, and bump it, which enables optimistic concurrency: read
+/// a value, compute the change, then apply it against the version read.
+pub struct VersionedLockedMap<K, V, A, S = DefaultHashBuilder> {
+    map: LockedMap<K, V, S>,
+    version: A,
+}
+
+impl<K, V, A> VersionedLockedMap<K, V, A>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    A: Fn(&mut V) -> &mut u64,
+{
+    /// Create a new versioned map.
+    ///
+    /// # Arguments
+    /// * `version` - An accessor to the version field of a value
+    ///
+    /// # Returns
+    /// A new map instance
+    pub fn new(version: A) -> Self {
+        Self::with_hasher(version, Default::default())
+    }
+}
+
+impl<K, V, A, S> VersionedLockedMap<K, V, A, S>
+where
+    K: Hash + Eq + Send + Sync,
+    V: Send + Sync,
+    A: Fn(&mut V) -> &mut u64,
+    S: BuildHasher + Send + Sync,
+{
+    /// Create a new versioned map with a custom hash builder.
+    ///
+    /// # Arguments
+    /// * `version` - An accessor to the version field of a value
+    /// * `hash_builder` - The hash builder to use
+    ///
+    /// # Returns
+    /// A new map instance
+    pub fn with_hasher(version: A, hash_builder: S) -> Self {
+        Self {
+            map: LockedMap::with_shards_and_capacity_and_hasher(DEFAULT_SHARDS, 0, hash_builder),
+            version,
+        }
+    }
+
+    /// Insert a key-value pair, keeping the version stored in the value.
+    ///
+    /// # Arguments
+    /// * `key` - The key to insert
+    /// * `value` - The value to insert
+    ///
+    /// # Returns
+    /// The previous value associated with the key, if any
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.map.insert(key, value).and_then(|old| old.try_owned())
+    }
+
+    /// Update a value if its version is the expected one, and bump the version.
+    ///
+    /// The version is checked, `f` applied and the version set to
+    /// `expected_version + 1` under the shard write lock, so concurrent
+    /// updates against the same version succeed at most once.
+    ///
+    /// # Arguments
+    /// * `key` - The key to update
+    /// * `expected_version` - The version the update was computed from
+    /// * `f` - A closure modifying the value
+    ///
+    /// # Returns
+    /// `Ok` if the update was applied, or the reason it was rejected
+    pub fn cas_version<Q, F>(&self, key: &Q, expected_version: u64, f: F) -> Result<(), CasError>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&mut V),
+    {
+        self.map
+            .alter(key, |v| {
+                let current = *(self.version)(v);
+                if current != expected_version {
+                    return Err(CasError::VersionMismatch { current });
+                }
+                f(v);
+                *(self.version)(v) = expected_version + 1;
+                Ok(())
+            })
+            .unwrap_or(Err(CasError::KeyNotFound))
+    }
+
+    /// Run a closure on the entry associated with the key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `f` - A closure that receives the key and value references
+    ///
+    /// # Returns
+    /// The result of the closure if the key exists, None otherwise
+    pub fn view<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&K, &V) -> R,
+    {
+        self.map.view(key, f)
+    }
+
+    /// Remove a key from the map.
+    ///
+    /// # Arguments
+    /// * `key` - The key to remove
+    ///
+    /// # Returns
+    /// The value that was removed, if the key existed
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.remove(key).and_then(|v| v.try_owned())
+    }
+
+    /// Get the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
@@ -4,9 +4,11 @@ use core::marker::PhantomData;
 use core::ops::Deref;
 
 use alloc::sync::Arc;
-use crossbeam_utils::CachePadded;
+use alloc::vec::Vec;
 use hashbrown::DefaultHashBuilder;
 
+use crate::hash::fixed::FixedMap;
+
 use super::traits::ShardStorage;
 
 /// A wrapper type that can hold either an owned value or a shared reference
@@ -56,6 +58,17 @@ impl<T> MaybeArc<T> {
             None
         }
     }
+
+    /// Get the value, cloning it only if it is still shared elsewhere.
+    pub fn into_owned(self) -> T
+    where
+        T: Clone,
+    {
+        match self {
+            MaybeArc::Owned(value) => value,
+            MaybeArc::Shared(arc) => Arc::unwrap_or_clone(arc),
+        }
+    }
 }
 
 impl<T> Deref for MaybeArc<T> {
@@ -166,17 +179,119 @@ where
 
     /// Get the shard that should contain the given key.
     ///
+    /// When the storage has a single shard, the key is not hashed at all.
+    /// The RCU map routes its operations through here, as its persistent
+    /// tables hash keys on their own. The locked map looks up its tables by
+    /// the hash of the key, so it hashes every key and picks the shard from
+    /// that hash instead.
+    ///
     /// # Arguments
     /// * `key` - The key to find the shard for
     ///
     /// # Returns
     /// A reference to the appropriate shard
     #[inline]
-    pub fn shard_for_key<Q: ?Sized + Hash>(&self, key: &Q) -> &CachePadded<Storage::Shard> {
+    pub fn shard_for_key<Q: ?Sized + Hash>(&self, key: &Q) -> &Storage::Shard {
+        if self.storage.shard_count() == 1 {
+            return self.storage.shard_for_hash(0);
+        }
         let hash = self.hash_key(key);
         self.storage.shard_for_hash(hash)
     }
 
+    /// Get the shard for a precomputed hash.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of a key, as computed by [`ConcurrentMap::hasher`]
+    ///
+    /// # Returns
+    /// A reference to the appropriate shard
+    #[inline]
+    pub fn shard_for_hash(&self, hash: u64) -> &Storage::Shard {
+        self.storage.shard_for_hash(hash)
+    }
+
+    /// Get the index of the shard that should contain the given key.
+    ///
+    /// # Arguments
+    /// * `key` - The key to find the shard index for
+    ///
+    /// # Returns
+    /// The index of the shard, less than `shard_count()`
+    #[inline]
+    pub fn shard_index_for_key<Q: ?Sized + Hash>(&self, key: &Q) -> usize {
+        let count = self.storage.shard_count();
+        if count == 1 {
+            return 0;
+        }
+        self.hash_key(key) as usize & (count - 1)
+    }
+
+    /// Check if two keys are stored in the same shard.
+    ///
+    /// Operations on several keys of the same shard only need one lock, or
+    /// one CAS for the RCU map.
+    ///
+    /// # Arguments
+    /// * `a` - The first key
+    /// * `b` - The second key
+    ///
+    /// # Returns
+    /// True if both keys map to the same shard index
+    #[inline]
+    pub fn same_shard<Q: ?Sized + Hash>(&self, a: &Q, b: &Q) -> bool {
+        self.shard_index_for_key(a) == self.shard_index_for_key(b)
+    }
+
+    /// Hash the keys and sort them by the index of their shard.
+    ///
+    /// # Arguments
+    /// * `keys` - The keys to group
+    ///
+    /// # Returns
+    /// A list of `(shard index, hash, key)` sorted by shard index
+    pub(crate) fn keys_by_shard<'q, Q: ?Sized + Hash>(
+        &self,
+        keys: &[&'q Q],
+    ) -> Vec<(usize, u64, &'q Q)> {
+        let mask = self.shard_count() - 1;
+        let mut grouped: Vec<_> = keys
+            .iter()
+            .map(|&key| {
+                let hash = self.hash_key(key);
+                (hash as usize & mask, hash, key)
+            })
+            .collect();
+        grouped.sort_unstable_by_key(|(index, _, _)| *index);
+        grouped
+    }
+
+    /// Hash the entries of a [`FixedMap`] and sort them by the index of their shard.
+    ///
+    /// # Arguments
+    /// * `fixed` - The map holding the entries to group
+    ///
+    /// # Returns
+    /// A list of `(shard index, hash, key, value)` sorted by shard index
+    pub(crate) fn entries_by_shard<'f, const CAP: usize, S2>(
+        &self,
+        fixed: &'f FixedMap<K, V, CAP, S2>,
+    ) -> Vec<(usize, u64, &'f K, &'f V)>
+    where
+        S2: BuildHasher,
+    {
+        let mask = self.shard_count() - 1;
+        let mut grouped: Vec<_> = fixed
+            .iter()
+            .map(|(key, value)| {
+                let hash = self.hash_key(key);
+                (hash as usize & mask, hash, key, value)
+            })
+            .collect();
+        grouped.sort_unstable_by_key(|(index, ..)| *index);
+        grouped
+    }
+
     /// Get the total number of shards in the map.
     ///
     /// # Returns
@@ -184,6 +299,52 @@ where
     pub fn shard_count(&self) -> usize {
         self.storage.shard_count()
     }
+
+    /// Get the hash builder used to route keys to shards.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Get the mask mapping a hash to a shard index.
+    ///
+    /// A key is stored in the shard at index
+    /// `map.hasher().hash_one(key) as usize & mask`, which stays stable for
+    /// the lifetime of the map.
+    ///
+    /// # Returns
+    /// `Some(shard_count - 1)` if the shard count is a power of two, `None` otherwise
+    pub fn shard_count_mask(&self) -> Option<usize> {
+        let count = self.shard_count();
+        count.is_power_of_two().then(|| count - 1)
+    }
+
+    /// Take a consistent snapshot of the size of the map.
+    ///
+    /// The entry counter is read once and every field is derived from that
+    /// read, so unlike separate `len()` and `is_empty()` calls the fields
+    /// never disagree.
+    ///
+    /// # Returns
+    /// The size statistics of the map
+    pub fn stats(&self) -> MapStats {
+        let len = self.storage.shard_len();
+        MapStats {
+            len,
+            shard_count: self.storage.shard_count(),
+            is_empty: len == 0,
+        }
+    }
+}
+
+/// A snapshot of the size of a concurrent map, see [`ConcurrentMap::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapStats {
+    /// The number of entries
+    pub len: usize,
+    /// The number of shards
+    pub shard_count: usize,
+    /// True if there were no entries
+    pub is_empty: bool,
 }
 
 impl<K, V, S, Storage> ConcurrentMap<K, V, S, Storage>
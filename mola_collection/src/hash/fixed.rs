@@ -80,6 +80,59 @@ where
         }
     }
 
+    /// Find the bucket index holding the key, probing at most `CAP` buckets.
+    fn find_index(&self, key: &K) -> Option<usize> {
+        let mut idx = self.hash_index(key);
+        for _ in 0..CAP {
+            match &self.buckets[idx] {
+                Bucket::Empty => return None,
+                Bucket::Occupied { key: ek, .. } if ek == key => return Some(idx),
+                _ => {}
+            }
+            idx = (idx + 1) & (CAP - 1);
+        }
+        None
+    }
+
+    /// Insert a key-value pair without panicking when the map is full.
+    ///
+    /// Replacing the value of an existing key always succeeds, even if the
+    /// map is full.
+    ///
+    /// # Returns
+    /// `Ok` with the previous value of the key, if any, or `Err` giving back
+    /// the pair if the key is new and the map is full
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        if !self.is_full() {
+            return Ok(self.insert(key, value));
+        }
+        match self.find_index(&key) {
+            Some(idx) => match &mut self.buckets[idx] {
+                Bucket::Occupied { value: ev, .. } => Ok(Some(core::mem::replace(ev, value))),
+                _ => unreachable!(),
+            },
+            None => Err((key, value)),
+        }
+    }
+
+    /// Insert pairs from an iterator, stopping at the first pair that does not fit.
+    ///
+    /// # Returns
+    /// `Err` with the rejected pair and the rest of the iterator if the map
+    /// became full
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), ((K, V), I::IntoIter)>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut iter = iter.into_iter();
+        for (key, value) in iter.by_ref() {
+            if let Err(pair) = self.try_insert(key, value) {
+                return Err((pair, iter));
+            }
+        }
+        Ok(())
+    }
+
     pub fn get(&self, key: &K) -> Option<&V> {
         let mut idx = self.hash_index(key);
         loop {
@@ -127,6 +180,111 @@ where
     pub fn is_full(&self) -> bool {
         self.len == CAP
     }
+
+    /// Get the longest probe sequence of the entries in the table.
+    ///
+    /// The probe length of an entry is its distance from the bucket its key
+    /// hashes to. Linear probing degrades when keys cluster, which shows up
+    /// as a long maximum probe, so this can be used to detect clustering.
+    ///
+    /// # Returns
+    /// The largest probe length, zero if every entry is in its home bucket
+    pub fn max_probe(&self) -> usize {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, bucket)| match bucket {
+                Bucket::Occupied { key, .. } => {
+                    Some(idx.wrapping_sub(self.hash_index(key)) & (CAP - 1))
+                }
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Iterate over the entries of the map, in bucket order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.buckets.iter().filter_map(|bucket| match bucket {
+            Bucket::Occupied { key, value } => Some((key, value)),
+            _ => None,
+        })
+    }
+}
+
+/// Extends the map with the pairs of an iterator.
+///
+/// # Panics
+/// Panics if a new key is inserted while the map is full, use
+/// [`FixedMap::try_extend`] to handle overflow instead.
+impl<K, V, const CAP: usize, S> Extend<(K, V)> for FixedMap<K, V, CAP, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            if self.try_insert(key, value).is_err() {
+                panic!("FixedMap is full");
+            }
+        }
+    }
+}
+
+/// Collects pairs into a new map.
+///
+/// # Panics
+/// Panics if the iterator yields more than `CAP` distinct keys.
+impl<K, V, const CAP: usize> FromIterator<(K, V)> for FixedMap<K, V, CAP>
+where
+    K: Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+/// An owning iterator over the entries of a [`FixedMap`].
+pub struct IntoIter<K, V, const CAP: usize> {
+    buckets: core::array::IntoIter<Bucket<K, V>, CAP>,
+    remaining: usize,
+}
+
+impl<K, V, const CAP: usize> Iterator for IntoIter<K, V, CAP> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buckets.by_ref().find_map(|bucket| match bucket {
+            Bucket::Occupied { key, value } => {
+                self.remaining -= 1;
+                Some((key, value))
+            }
+            _ => None,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V, const CAP: usize> ExactSizeIterator for IntoIter<K, V, CAP> {}
+
+impl<K, V, const CAP: usize, S> IntoIterator for FixedMap<K, V, CAP, S>
+where
+    S: BuildHasher,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, CAP>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            buckets: self.buckets.into_iter(),
+            remaining: self.len,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -180,4 +338,72 @@ mod tests {
         assert_eq!(map.remove(&1), Some("one"));
         assert_eq!(map.get(&5), Some(&"five"));
     }
+
+    #[test]
+    fn test_extend() {
+        let mut map: FixedMap<_, _, 8> = FixedMap::new();
+        map.extend((0..5).map(|i| (i, i * 10)));
+        assert_eq!(map.len(), 5);
+        for i in 0..5 {
+            assert_eq!(map.get(&i), Some(&(i * 10)));
+        }
+
+        let collected: FixedMap<_, _, 8> = (0..5).map(|i| (i, i * 10)).collect();
+        assert_eq!(collected.len(), 5);
+        assert_eq!(collected.get(&4), Some(&40));
+    }
+
+    #[test]
+    fn test_try_extend_overflow() {
+        let mut map: FixedMap<_, _, 4> = FixedMap::new();
+        let (rejected, rest) = map.try_extend((0..6).map(|i| (i, i))).unwrap_err();
+        assert!(map.is_full());
+        assert_eq!(rejected, (4, 4));
+        assert_eq!(rest.collect::<alloc::vec::Vec<_>>(), [(5, 5)]);
+
+        // Existing keys can still be replaced once full
+        assert_eq!(map.try_insert(0, 100), Ok(Some(0)));
+        assert!(map.try_extend([(1, 10), (2, 20)]).is_ok());
+        assert_eq!(map.get(&2), Some(&20));
+    }
+
+    #[test]
+    fn test_max_probe() {
+        let mut map: FixedMap<_, _, 64> = FixedMap::new();
+        assert_eq!(map.max_probe(), 0);
+
+        // Keys sharing a home bucket are laid out one after another
+        let home = map.hash_index(&0);
+        let cluster: alloc::vec::Vec<i32> = (0..)
+            .filter(|k| map.hash_index(k) == home)
+            .take(5)
+            .collect();
+        for &key in &cluster {
+            map.insert(key, key);
+        }
+        assert_eq!(map.max_probe(), 4);
+
+        // Removing the tail of the cluster shortens the longest probe
+        map.remove(&cluster[4]);
+        assert_eq!(map.max_probe(), 3);
+        for &key in &cluster[..4] {
+            assert_eq!(map.get(&key), Some(&key));
+        }
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut map: FixedMap<_, _, 8> = FixedMap::new();
+        for i in 0..6 {
+            map.insert(i, i * 10);
+        }
+        // Leave a tombstone behind
+        map.remove(&3);
+
+        let iter = map.into_iter();
+        assert_eq!(iter.len(), 5);
+        let mut entries: alloc::vec::Vec<_> = iter.collect();
+        entries.sort();
+        assert_eq!(entries, [(0, 0), (1, 10), (2, 20), (4, 40), (5, 50)]);
+    }
 }
@@ -1,2 +1,4 @@
+pub mod cached;
 pub mod concurrent;
 pub mod fixed;
+pub mod seeded;
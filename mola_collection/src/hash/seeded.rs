@@ -0,0 +1,8 @@
+/// A hash builder whose hashes only depend on a seed.
+///
+/// Unlike the default hash builder, which is randomly seeded on creation, two
+/// builders with the same seed produce the same hashes on every run. Maps
+/// built with one place and iterate their keys in a reproducible order, which
+/// helps with order-dependent tests. It offers no protection against
+/// collision attacks, so avoid it for untrusted keys.
+pub type SeededHashBuilder = foldhash::fast::FixedState;
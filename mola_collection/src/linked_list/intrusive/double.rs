@@ -5,13 +5,50 @@ use mola_collection_derive::Node;
 use super::traits::{Link, LinkWithPrev, List, Node};
 
 /// A node in a doubly linked list.
-#[derive(Node)]
+///
+/// The node is `repr(C)` so that `link` stays at offset zero, since pointers
+/// to the node and to its link are cast into each other.
+#[derive(Node, Debug)]
 #[node(crate_path = "crate")]
+#[repr(C)]
 pub struct DoubleNode<T> {
     link: DoubleLink,
     data: T,
 }
 
+impl<T> DoubleNode<T> {
+    /// Create a new unlinked node holding the given data.
+    pub fn new(data: T) -> Self {
+        Self {
+            link: DoubleLink::default(),
+            data,
+        }
+    }
+
+    /// Consume the node and return its data.
+    pub fn into_data(self) -> T {
+        self.data
+    }
+}
+
+/// Build an array of unlinked nodes holding the given values, in order.
+///
+/// The nodes can then be pushed into a list, and must not be moved while
+/// they are linked.
+pub fn nodes_from<const N: usize, T>(values: [T; N]) -> [DoubleNode<T>; N] {
+    values.map(DoubleNode::new)
+}
+
+/// Cloning copies the data into a fresh, unlinked node.
+///
+/// The clone is detached from any list the original node is in, and the
+/// original node is left untouched.
+impl<T: Clone> Clone for DoubleNode<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.data.clone())
+    }
+}
+
 impl<T: Default> Default for DoubleNode<T> {
     fn default() -> Self {
         Self {
@@ -55,6 +92,13 @@ impl LinkWithPrev for DoubleLink {
 }
 
 impl Node for DoubleLink {
+    const DETACHES_WITHOUT_PARENT: bool = true;
+
+    #[inline]
+    fn prev_node(&self) -> Option<NonNull<Self>> {
+        self.prev()
+    }
+
     #[inline]
     fn append_to<L>(&mut self, list: &mut L)
     where
@@ -62,6 +106,11 @@ impl Node for DoubleLink {
     {
         let self_ptr = NonNull::from(&mut *self);
         self.set_next(list.next());
+        debug_assert_ne!(
+            self.next(),
+            Some(self_ptr),
+            "Node links to itself, it was likely pushed while already at the head"
+        );
         if let Some(next) = self.next() {
             let next = unsafe { &mut *next.as_ptr() };
             next.set_prev(Some(self_ptr));
@@ -70,6 +119,18 @@ impl Node for DoubleLink {
         list.set_next(Some(self_ptr.cast()));
     }
 
+    #[inline]
+    unsafe fn insert_after(&mut self, prev: &mut Self) {
+        let self_ptr = NonNull::from(&mut *self);
+        self.set_next(prev.next());
+        if let Some(next) = self.next() {
+            let next = unsafe { &mut *next.as_ptr() };
+            next.set_prev(Some(self_ptr));
+        }
+        self.set_prev(Some(NonNull::from(&mut *prev)));
+        prev.set_next(Some(self_ptr));
+    }
+
     #[inline]
     unsafe fn detach<L>(&mut self, parent: Option<&mut L>)
     where
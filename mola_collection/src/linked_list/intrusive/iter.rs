@@ -1,11 +1,18 @@
+use core::marker::PhantomData;
 use core::ptr::NonNull;
 
-use super::traits::{List, Node};
+use super::traits::{LinkWithPrev, List, Node};
 
 /// An iterator over a linked list.
+///
+/// When the nodes link back to their previous node, it can also iterate
+/// from the back, starting at the tail of the list. Both ends stop once they
+/// meet, so no node is yielded twice.
 pub struct LinkedListIter<'a, T: Node, L: List> {
-    _list: &'a L,
+    list: &'a L,
     current: Option<NonNull<T>>,
+    // Only set once iterating from the back
+    back: Option<NonNull<T>>,
 }
 
 impl<'a, T, L> LinkedListIter<'a, T, L>
@@ -21,7 +28,8 @@ where
     pub unsafe fn new(list: &'a L) -> Self {
         Self {
             current: list.head().map(|n| n.cast()),
-            _list: list,
+            back: None,
+            list,
         }
     }
 }
@@ -35,11 +43,44 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         self.current.inspect(|current| {
-            self.current = unsafe { current.as_ref().next().map(|n| n.cast()) };
+            if self.back == Some(*current) {
+                self.current = None;
+                self.back = None;
+            } else {
+                self.current = unsafe { current.as_ref().next().map(|n| n.cast()) };
+            }
         })
     }
 }
 
+impl<'a, T, L> DoubleEndedIterator for LinkedListIter<'a, T, L>
+where
+    T: Node + LinkWithPrev,
+    L: List<Target = T>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        let back = self
+            .back
+            .or_else(|| self.list.tail().map(|n| n.cast()))
+            .unwrap_or_else(|| {
+                // The list does not track its tail, find it from the front
+                let mut last = current;
+                while let Some(next) = unsafe { last.as_ref().next() } {
+                    last = next.cast();
+                }
+                last
+            });
+        if back == current {
+            self.current = None;
+            self.back = None;
+        } else {
+            self.back = unsafe { back.as_ref().prev().map(|n| n.cast()) };
+        }
+        Some(back)
+    }
+}
+
 unsafe impl<'a, T, L> Send for LinkedListIter<'a, T, L>
 where
     T: Node + Send,
@@ -53,3 +94,62 @@ where
     L: List<Target = T>,
 {
 }
+
+/// A mutable iterator over a linked list.
+///
+/// Every node is yielded once as an exclusive reference, and the list stays
+/// mutably borrowed while the iterator is alive, so the yielded references
+/// never alias.
+pub struct LinkedListIterMut<'a, T: Node, L: List> {
+    _list: PhantomData<&'a mut L>,
+    current: Option<NonNull<T>>,
+}
+
+impl<'a, T, L> LinkedListIterMut<'a, T, L>
+where
+    T: Node,
+    L: List<Target = T>,
+{
+    /// Creates a new mutable iterator over the given list.
+    ///
+    /// # Safety
+    ///
+    /// The nodes of the list must be live, and must not form a cycle, or a
+    /// node would be yielded twice.
+    pub unsafe fn new(list: &'a mut L) -> Self {
+        Self {
+            current: list.head().map(|n| n.cast()),
+            _list: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, L> Iterator for LinkedListIterMut<'a, T, L>
+where
+    T: Node,
+    L: List<Target = T>,
+{
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current.map(|current| {
+            let node = unsafe { &mut *current.as_ptr() };
+            self.current = node.next().map(|n| n.cast());
+            node
+        })
+    }
+}
+
+unsafe impl<'a, T, L> Send for LinkedListIterMut<'a, T, L>
+where
+    T: Node + Send,
+    L: List<Target = T>,
+{
+}
+
+unsafe impl<'a, T, L> Sync for LinkedListIterMut<'a, T, L>
+where
+    T: Node + Sync,
+    L: List<Target = T>,
+{
+}
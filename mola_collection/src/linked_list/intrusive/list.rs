@@ -1,11 +1,52 @@
+use core::cmp::Ordering;
+use core::fmt;
 use core::ptr::NonNull;
 
-use super::traits::{Link, LinkWithPrev, List, Node};
+use super::traits::{Link, LinkWithPrev, List, Node, NodeWithData};
+
+/// The first structural violation found by [`LinkedList::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListIntegrityError {
+    /// A node links back to an earlier node of the list.
+    Cycle,
+    /// The number of linked nodes differs from the cached count.
+    CountMismatch {
+        /// The cached count.
+        expected: usize,
+        /// The number of nodes reachable from the head.
+        found: usize,
+    },
+    /// The tracked tail is not the last node reachable from the head.
+    TailMismatch,
+    /// A node does not link back to the node before it.
+    BrokenPrevLink {
+        /// The position of the node from the head.
+        index: usize,
+    },
+}
+
+impl fmt::Display for ListIntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle => write!(f, "list contains a cycle"),
+            Self::CountMismatch { expected, found } => {
+                write!(f, "list counts {expected} nodes but links {found}")
+            }
+            Self::TailMismatch => write!(f, "tail is not the last linked node"),
+            Self::BrokenPrevLink { index } => {
+                write!(f, "node {index} does not link back to its predecessor")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ListIntegrityError {}
 
 /// A generic intrusive linked list.
 #[derive(Debug)]
 pub struct LinkedList<T: Node> {
     head: Option<NonNull<T>>,
+    tail: Option<NonNull<T>>,
     count: usize,
 }
 
@@ -17,11 +58,406 @@ where
     pub const fn new() -> Self {
         LinkedList {
             head: None,
+            tail: None,
             count: 0,
         }
     }
 }
 
+impl<T> LinkedList<T>
+where
+    T: Node<Target = T>,
+{
+    /// Insert a node right after another node of this list.
+    ///
+    /// # Safety
+    ///
+    /// `prev` must be a node in this list, and `node` must not be linked into
+    /// any list.
+    pub unsafe fn insert_after(&mut self, prev: NonNull<T>, node: NonNull<T>) {
+        unsafe {
+            (*node.as_ptr()).insert_after(&mut *prev.as_ptr());
+        }
+        if self.tail == Some(prev) {
+            self.tail = Some(node);
+        }
+        self.count += 1;
+    }
+
+    /// Push a node to the back of the list.
+    ///
+    /// The list keeps track of its tail, so this takes constant time and a
+    /// list filled with `push_back` and emptied with `pop` is a FIFO queue.
+    pub fn push_back(&mut self, node: NonNull<T>) {
+        match self.tail {
+            Some(tail) => unsafe { self.insert_after(tail, node) },
+            None => self.push(node),
+        }
+    }
+
+    /// Remove a node given its parent, checking the parent first.
+    ///
+    /// This is the safe counterpart of [`List::quick_remove`]. With debug
+    /// assertions, the list is scanned for the actual parent of the node,
+    /// which must be `parent`, or `None` if the node is the head, before
+    /// taking the fast path. Without debug assertions, `parent` is not
+    /// checked and the node is removed with [`List::remove`] instead.
+    /// Either way the scan is `O(n)`, and only nodes of the list are read.
+    ///
+    /// # Arguments
+    /// * `node` - The node to remove
+    /// * `parent` - The node right before `node`, or `None` if it is the head
+    ///
+    /// # Returns
+    /// The removed node, or `None` if it is not in the list
+    ///
+    /// # Panics
+    /// With debug assertions, panics if `parent` does not precede `node`
+    pub fn remove_verified(
+        &mut self,
+        node: NonNull<T>,
+        parent: Option<NonNull<T>>,
+    ) -> Option<NonNull<T>> {
+        if !cfg!(debug_assertions) {
+            return self.remove(node);
+        }
+
+        let actual = if self.head == Some(node) {
+            None
+        } else {
+            let found = unsafe {
+                self.iter()
+                    .find(|current| current.as_ref().next() == Some(node))
+            };
+            Some(found?)
+        };
+        assert_eq!(parent, actual, "Parent does not precede the node");
+        unsafe { self.quick_remove(node, parent) }
+    }
+
+    /// Count the nodes by walking the list from the head.
+    ///
+    /// Unlike [`List::count`], this never reads the cached counter, so it can
+    /// serve as an oracle to check the counter against.
+    pub fn len_by_traversal(&self) -> usize {
+        unsafe { self.iter().count() }
+    }
+
+    /// Check the structure of the list in a single walk from the head.
+    ///
+    /// The walk stops once it passes the cached count, so a corrupted list
+    /// never hangs it. Along the way it checks that the list is acyclic,
+    /// that the number of nodes matches the count, that the tail is the last
+    /// node, and, for nodes linking back to their previous node, that every
+    /// prev pointer matches. It is meant for tests after complex
+    /// manipulations of the list.
+    ///
+    /// # Returns
+    /// `Ok` if the list is well formed, or the first violation found
+    pub fn validate(&self) -> Result<(), ListIntegrityError> {
+        let mut prev: Option<NonNull<T>> = None;
+        let mut current = self.head;
+        let mut found = 0;
+        while let Some(node) = current {
+            if found == self.count {
+                // More nodes than counted, either looping or miscounted
+                if self.has_cycle() {
+                    return Err(ListIntegrityError::Cycle);
+                }
+                return Err(ListIntegrityError::CountMismatch {
+                    expected: self.count,
+                    found: self.len_by_traversal(),
+                });
+            }
+            let node_ref = unsafe { node.as_ref() };
+            if T::DETACHES_WITHOUT_PARENT && node_ref.prev_node() != prev {
+                return Err(ListIntegrityError::BrokenPrevLink { index: found });
+            }
+            prev = Some(node);
+            current = node_ref.next();
+            found += 1;
+        }
+
+        if found != self.count {
+            return Err(ListIntegrityError::CountMismatch {
+                expected: self.count,
+                found,
+            });
+        }
+        if self.tail != prev {
+            return Err(ListIntegrityError::TailMismatch);
+        }
+        Ok(())
+    }
+
+    /// Check for a cycle with Floyd's algorithm, which takes `O(n)` steps
+    /// and constant memory even on a looping list.
+    fn has_cycle(&self) -> bool {
+        let next = |node: NonNull<T>| unsafe { node.as_ref() }.next();
+        let mut slow = self.head;
+        let mut fast = self.head;
+        loop {
+            fast = fast.and_then(next).and_then(next);
+            slow = slow.and_then(next);
+            if fast.is_none() {
+                return false;
+            }
+            if fast == slow {
+                return true;
+            }
+        }
+    }
+
+    /// Iterate over the nodes along with their position from the head.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the linked list is not modified while iterating.
+    pub unsafe fn enumerate(&self) -> impl Iterator<Item = (usize, NonNull<T>)> + '_ {
+        unsafe { self.iter().enumerate() }
+    }
+
+    /// Splice all nodes of another list into this list right after a node.
+    ///
+    /// The nodes of `other` keep their order and are inserted immediately after
+    /// `after`, or at the head of the list if `after` is `None`.
+    ///
+    /// # Safety
+    ///
+    /// `after`, if provided, must be a node in this list, and `other` must not
+    /// share any node with this list.
+    pub unsafe fn splice_after(&mut self, after: Option<NonNull<T>>, other: LinkedList<T>) {
+        unsafe {
+            // Splicing after the tail, or into an empty list, moves the tail
+            let extends_tail = after == self.tail;
+            let mut anchor = after;
+            let mut current = other.head;
+            while let Some(node) = current {
+                let node_ref = &mut *node.as_ptr();
+                current = node_ref.next();
+                if let Some(anchor) = anchor {
+                    node_ref.insert_after(&mut *anchor.as_ptr());
+                } else {
+                    node_ref.append_to(self);
+                }
+                anchor = Some(node);
+            }
+            if extends_tail && other.tail.is_some() {
+                self.tail = other.tail;
+            }
+            self.count += other.count;
+        }
+    }
+}
+
+impl<T> LinkedList<T>
+where
+    T: Node<Target = T> + LinkWithPrev,
+{
+    /// Rewrite the prev pointer of every node from the next pointers.
+    ///
+    /// This walks the list from the head and points each node back to its
+    /// actual predecessor, with the head pointing to `None`. It is a recovery
+    /// aid after manual surgery left the prev pointers out of sync.
+    ///
+    /// # Safety
+    ///
+    /// The next pointers must form a valid, acyclic chain of live nodes.
+    pub unsafe fn repair_prev(&mut self) {
+        unsafe {
+            let mut prev: Option<NonNull<T>> = None;
+            let mut current = self.head;
+            while let Some(node) = current {
+                let node_ref = &mut *node.as_ptr();
+                node_ref.set_prev(prev);
+                prev = Some(node);
+                current = node_ref.next();
+            }
+        }
+    }
+
+    /// Check the prev pointers against the next pointers.
+    ///
+    /// This walks the list from the head and checks that the head links back
+    /// to `None` and every other node to its actual predecessor. It verifies
+    /// the doubly-linked invariants after manual surgery, see
+    /// [`repair_prev`](Self::repair_prev) to restore them.
+    ///
+    /// # Returns
+    /// `true` if every prev pointer is consistent
+    pub fn prev_links_consistent(&self) -> bool {
+        let mut prev: Option<NonNull<T>> = None;
+        let mut current = self.head;
+        while let Some(node) = current {
+            let node_ref = unsafe { node.as_ref() };
+            if node_ref.prev() != prev {
+                return false;
+            }
+            prev = Some(node);
+            current = node_ref.next();
+        }
+        true
+    }
+
+    /// Pop a node from the back of the list.
+    ///
+    /// The tail links back to its previous node, so this takes constant time
+    /// and, along with [`push_back`](Self::push_back) and [`List::pop`], lets
+    /// the list serve as a deque.
+    ///
+    /// # Returns
+    /// The old tail, or `None` if the list is empty
+    pub fn pop_back(&mut self) -> Option<NonNull<T>> {
+        let tail = self.tail?;
+        unsafe {
+            let tail_ref = &mut *tail.as_ptr();
+            let prev = tail_ref.prev();
+            if let Some(prev) = prev {
+                tail_ref.detach(Some(&mut *prev.as_ptr()));
+            } else {
+                tail_ref.detach(Some(self));
+            }
+            self.tail = prev;
+        }
+        self.count -= 1;
+        Some(tail)
+    }
+}
+
+impl<T> LinkedList<T>
+where
+    T: Node<Target = T> + NodeWithData,
+{
+    /// Pop the head node along with a raw pointer to its data.
+    ///
+    /// # Safety
+    ///
+    /// The data pointer is only valid as long as the popped node is alive and
+    /// its data is not mutated.
+    pub unsafe fn pop_with_data(&mut self) -> Option<(NonNull<T>, *const T::Data)> {
+        self.pop()
+            .map(|node| (node, unsafe { node.as_ref() }.data() as *const T::Data))
+    }
+
+    /// Count the nodes whose data satisfies a predicate.
+    ///
+    /// # Arguments
+    /// * `pred` - The predicate to test the data of each node against
+    ///
+    /// # Returns
+    /// The number of matching nodes
+    pub fn count_matching<F>(&self, pred: F) -> usize
+    where
+        F: Fn(&T::Data) -> bool,
+    {
+        unsafe { self.iter().filter(|node| pred(node.as_ref().data())).count() }
+    }
+
+    /// Update the data of every node and remove the nodes to drop, in one pass.
+    ///
+    /// `f` may mutate the data and returns whether to keep the node. The
+    /// removed nodes are unlinked and returned in a new list, in their
+    /// original order, so they can be reclaimed.
+    ///
+    /// # Safety
+    ///
+    /// Every node must be alive, and `f` must not access this list.
+    pub unsafe fn retain_map<F>(&mut self, mut f: F) -> LinkedList<T>
+    where
+        F: FnMut(&mut T::Data) -> bool,
+    {
+        unsafe {
+            let mut removed = LinkedList::new();
+            let mut removed_tail: Option<NonNull<T>> = None;
+            let mut prev: Option<NonNull<T>> = None;
+            let mut current = self.head;
+            while let Some(node) = current {
+                let node_ref = &mut *node.as_ptr();
+                current = node_ref.next();
+                if f(node_ref.data_mut()) {
+                    prev = Some(node);
+                    continue;
+                }
+
+                if let Some(prev) = prev {
+                    node_ref.detach(Some(&mut *prev.as_ptr()));
+                } else {
+                    node_ref.detach(Some(&mut *self));
+                }
+                if self.tail == Some(node) {
+                    self.tail = prev;
+                }
+                self.count -= 1;
+
+                if let Some(tail) = removed_tail {
+                    removed.insert_after(tail, node);
+                } else {
+                    removed.push(node);
+                }
+                removed_tail = Some(node);
+            }
+            removed
+        }
+    }
+}
+
+impl<T> LinkedList<T>
+where
+    T: Node<Target = T> + NodeWithData,
+    T::Data: Clone,
+{
+    /// Pop the head node and return a clone of its data.
+    ///
+    /// The popped node is unlinked and its links are reset, so it can be
+    /// pushed into a list again.
+    pub fn pop_data(&mut self) -> Option<T::Data> {
+        self.pop().map(|node| unsafe {
+            let node_ref = &mut *node.as_ptr();
+            node_ref.set_next(None);
+            node_ref.data().clone()
+        })
+    }
+}
+
+impl<T> LinkedList<T>
+where
+    T: Node<Target = T> + NodeWithData,
+    T::Data: Ord,
+{
+    /// Insert a node at its sorted position, skipping duplicates.
+    ///
+    /// The list is treated as an ordered set: the node is inserted before the
+    /// first node with greater data, unless a node with equal data already exists.
+    ///
+    /// # Returns
+    /// `true` if the node was inserted, `false` if a duplicate exists
+    ///
+    /// # Safety
+    ///
+    /// The list must already be sorted in ascending order, and the node must
+    /// not be linked into any list.
+    pub unsafe fn insert_unique_sorted(&mut self, node: NonNull<T>) -> bool {
+        unsafe {
+            let data = node.as_ref().data();
+            let mut prev: Option<NonNull<T>> = None;
+            for current in self.iter() {
+                match current.as_ref().data().cmp(data) {
+                    Ordering::Less => prev = Some(current),
+                    Ordering::Equal => return false,
+                    Ordering::Greater => break,
+                }
+            }
+
+            if let Some(prev) = prev {
+                self.insert_after(prev, node);
+            } else {
+                self.push(node);
+            }
+            true
+        }
+    }
+}
+
 impl<T> Link for LinkedList<T>
 where
     T: Node,
@@ -67,12 +503,19 @@ where
         self.set_next(head);
     }
 
+    fn tail(&self) -> Option<NonNull<T>> {
+        self.tail
+    }
+
     fn push(&mut self, node: NonNull<T>) {
         unsafe {
             let node_ref = &mut *node.as_ptr();
             node_ref.append_to(self);
             self.count += 1;
         }
+        if self.tail.is_none() {
+            self.tail = Some(node);
+        }
     }
 
     fn pop(&mut self) -> Option<NonNull<T>> {
@@ -82,6 +525,9 @@ where
                 head_ref.detach(Some(self));
                 self.count -= 1;
             }
+            if self.head.is_none() {
+                self.tail = None;
+            }
         })
     }
 
@@ -96,6 +542,9 @@ where
                     } else {
                         node_ptr.detach(Some(self));
                     }
+                    if self.tail == Some(current) {
+                        self.tail = prev;
+                    }
                     self.count -= 1;
                     return Some(current);
                 }
@@ -112,12 +561,32 @@ where
     ) -> Option<NonNull<T>> {
         unsafe {
             let node_ref = &mut *node.as_ptr();
+            let is_tail = self.tail == Some(node);
             if let Some(parent) = parent {
                 node_ref.detach(Some(&mut *parent.as_ptr()));
+                if is_tail {
+                    self.tail = Some(parent);
+                }
             } else if self.head == Some(node) {
                 node_ref.detach(Some(self));
-            } else {
+                if is_tail {
+                    self.tail = None;
+                }
+            } else if T::DETACHES_WITHOUT_PARENT {
+                if is_tail {
+                    self.tail = node_ref.prev_node();
+                }
                 node_ref.detach::<T>(None);
+            } else {
+                // The node cannot reach its parent, look it up from the head
+                let parent = self
+                    .iter()
+                    .find(|current| current.as_ref().next() == Some(node))
+                    .expect("Node is not in the list");
+                node_ref.detach(Some(&mut *parent.as_ptr()));
+                if is_tail {
+                    self.tail = Some(parent);
+                }
             }
             self.count -= 1;
             Some(node)
@@ -140,6 +609,7 @@ where
     fn default() -> Self {
         Self {
             head: None,
+            tail: None,
             count: 0,
         }
     }
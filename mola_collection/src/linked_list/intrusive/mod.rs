@@ -8,6 +8,11 @@
 //! - [`list::LinkedList`]: A generic implementation of a linked list.
 //! - [`single::SingleLink`] and [`double::DoubleLink`]: Link types for creating singly and doubly linked lists.
 //! - [`node::ListNode`]: A node that can be embedded in a struct to make it part of a linked list.
+//! - [`pool::ObjectPool`]: A pool reusing nodes through an intrusive free-list.
+//! - [`stack::Stack`]: A LIFO stack of singly linked nodes.
+//! - [`timer::TimerList`]: A hashed timer wheel chaining doubly linked timer nodes.
+//! - [`prelude`]: The traits, list and link types, and the `Node` derive needed
+//!   to define custom nodes in another crate.
 //!
 //! ## Safety
 //!
@@ -25,10 +30,25 @@ pub mod single;
 pub mod double;
 pub mod list;
 pub mod iter;
+pub mod pool;
+pub mod stack;
+pub mod timer;
 
 pub mod derive {
     pub use mola_collection_derive::Node;
 }
 
+/// Everything needed to derive and use custom nodes with a single import.
+///
+/// The code generated by `#[derive(Node)]` calls trait methods on the link
+/// field, so the traits must be in scope wherever the derive is used.
+pub mod prelude {
+    pub use super::derive::Node;
+    pub use super::double::DoubleLink;
+    pub use super::list::LinkedList;
+    pub use super::single::SingleLink;
+    pub use super::traits::{Link, LinkWithPrev, List, Node, NodeWithData};
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,71 @@
+use alloc::boxed::Box;
+use core::ptr::NonNull;
+
+use super::list::LinkedList;
+use super::single::SingleNode;
+use super::traits::{Link, List};
+
+/// A pool of heap allocated nodes, reused through an intrusive free-list.
+///
+/// Released nodes are pushed onto a free-list threaded through their own
+/// links, so reusing them costs no allocation. Nodes handed out by
+/// [`ObjectPool::acquire`] are owned by the caller until they are released,
+/// and nodes that are never released are leaked.
+pub struct ObjectPool<T> {
+    free: LinkedList<SingleNode<T>>,
+}
+
+impl<T> ObjectPool<T> {
+    /// Create a new, empty pool.
+    pub const fn new() -> Self {
+        Self {
+            free: LinkedList::new(),
+        }
+    }
+
+    /// Get the number of released nodes waiting to be reused.
+    pub fn free_count(&self) -> usize {
+        self.free.count()
+    }
+
+    /// Take a node from the pool, allocating one if the pool is empty.
+    ///
+    /// A reused node keeps the data it was released with, while a new node
+    /// starts with the default value. The node is not linked into any list.
+    pub fn acquire(&mut self) -> NonNull<SingleNode<T>>
+    where
+        T: Default,
+    {
+        match self.free.pop() {
+            Some(node) => {
+                unsafe { (*node.as_ptr()).set_next(None) };
+                node
+            }
+            None => NonNull::from(Box::leak(Box::new(SingleNode::default()))),
+        }
+    }
+
+    /// Give a node back to the pool for reuse.
+    ///
+    /// # Safety
+    ///
+    /// The node must have been acquired from this pool, must not be linked
+    /// into any list, and must not be used after being released.
+    pub unsafe fn release(&mut self, node: NonNull<SingleNode<T>>) {
+        self.free.push(node);
+    }
+}
+
+impl<T> Default for ObjectPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for ObjectPool<T> {
+    fn drop(&mut self) {
+        while let Some(node) = self.free.pop() {
+            drop(unsafe { Box::from_raw(node.as_ptr()) });
+        }
+    }
+}
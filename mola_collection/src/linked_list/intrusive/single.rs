@@ -5,13 +5,50 @@ use mola_collection_derive::Node;
 use super::traits::{Link, List, Node};
 
 /// A node in a singly linked list.
-#[derive(Node)]
+///
+/// The node is `repr(C)` so that `link` stays at offset zero, since pointers
+/// to the node and to its link are cast into each other.
+#[derive(Node, Debug)]
 #[node(crate_path = "crate")]
+#[repr(C)]
 pub struct SingleNode<T> {
     link: SingleLink,
     data: T,
 }
 
+impl<T> SingleNode<T> {
+    /// Create a new unlinked node holding the given data.
+    pub fn new(data: T) -> Self {
+        Self {
+            link: SingleLink::default(),
+            data,
+        }
+    }
+
+    /// Consume the node and return its data.
+    pub fn into_data(self) -> T {
+        self.data
+    }
+}
+
+/// Build an array of unlinked nodes holding the given values, in order.
+///
+/// The nodes can then be pushed into a list, and must not be moved while
+/// they are linked.
+pub fn nodes_from<const N: usize, T>(values: [T; N]) -> [SingleNode<T>; N] {
+    values.map(SingleNode::new)
+}
+
+/// Cloning copies the data into a fresh, unlinked node.
+///
+/// The clone is detached from any list the original node is in, and the
+/// original node is left untouched.
+impl<T: Clone> Clone for SingleNode<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.data.clone())
+    }
+}
+
 impl<T: Default> Default for SingleNode<T> {
     fn default() -> Self {
         Self {
@@ -47,8 +84,14 @@ impl Node for SingleLink {
     where
         L: List<Target = Self>,
     {
+        let self_ptr = NonNull::from(&mut *self);
         self.set_next(list.next());
-        list.set_next(Some(NonNull::from(self).cast()));
+        debug_assert_ne!(
+            self.next(),
+            Some(self_ptr),
+            "Node links to itself, it was likely pushed while already at the head"
+        );
+        list.set_next(Some(self_ptr.cast()));
     }
     
     #[inline]
@@ -0,0 +1,57 @@
+use core::ptr::NonNull;
+
+use super::list::LinkedList;
+use super::single::SingleNode;
+use super::traits::List;
+
+/// An intrusive LIFO stack of singly linked nodes.
+///
+/// This is a thin wrapper over [`LinkedList`] that only exposes operations
+/// on the head, making the last-in first-out intent explicit.
+#[derive(Debug)]
+pub struct Stack<T> {
+    list: LinkedList<SingleNode<T>>,
+}
+
+impl<T> Stack<T> {
+    /// Create a new, empty stack.
+    pub const fn new() -> Self {
+        Self {
+            list: LinkedList::new(),
+        }
+    }
+
+    /// Push a node on top of the stack.
+    ///
+    /// The node must outlive its time on the stack and must not be linked
+    /// into any other list.
+    pub fn push(&mut self, node: NonNull<SingleNode<T>>) {
+        self.list.push(node);
+    }
+
+    /// Pop the node on top of the stack.
+    pub fn pop(&mut self) -> Option<NonNull<SingleNode<T>>> {
+        self.list.pop()
+    }
+
+    /// Get the node on top of the stack without removing it.
+    pub fn peek(&self) -> Option<NonNull<SingleNode<T>>> {
+        self.list.head()
+    }
+
+    /// Check if the stack is empty.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Get the number of nodes on the stack.
+    pub fn len(&self) -> usize {
+        self.list.count()
+    }
+}
+
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
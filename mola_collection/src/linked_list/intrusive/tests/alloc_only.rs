@@ -0,0 +1,34 @@
+//! Tests using only `core` and `alloc`, guarding the `no_std` promise of the
+//! intrusive list. Nothing here may reference `std`.
+
+use alloc::vec::Vec;
+use core::ptr::NonNull;
+
+use crate::linked_list::intrusive::{
+    list::LinkedList,
+    single::SingleNode,
+    traits::{List, NodeWithData},
+};
+
+#[test]
+fn test_single_list_iter_alloc_only() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut node1 = SingleNode::<i32>::default();
+    *node1.data_mut() = 1;
+    let mut node2 = SingleNode::<i32>::default();
+    *node2.data_mut() = 2;
+    let mut node3 = SingleNode::<i32>::default();
+    *node3.data_mut() = 3;
+
+    list.push(NonNull::from(&mut node1));
+    list.push(NonNull::from(&mut node2));
+    list.push(NonNull::from(&mut node3));
+
+    let mut values = Vec::new();
+    unsafe {
+        for node in list.iter() {
+            values.push(*node.as_ref().data());
+        }
+    }
+    assert_eq!(values, [3, 2, 1]);
+}
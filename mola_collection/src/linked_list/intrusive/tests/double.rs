@@ -5,8 +5,8 @@ use std::vec;
 use core::ptr::NonNull;
 
 use crate::linked_list::intrusive::{
-    double::DoubleNode,
-    list::LinkedList,
+    double::{DoubleNode, nodes_from},
+    list::{LinkedList, ListIntegrityError},
     traits::{Link, LinkWithPrev, List, NodeWithData},
 };
 
@@ -141,3 +141,376 @@ fn test_double_list_quick_remove() {
     assert_eq!(list.count(), 2);
 }
 
+#[test]
+fn test_double_list_insert_unique_sorted() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut nodes = [2, 1, 2, 3].map(|v| {
+        let mut node = DoubleNode::<i32>::default();
+        *node.data_mut() = v;
+        node
+    });
+
+    let inserted: vec::Vec<bool> = nodes
+        .iter_mut()
+        .map(|node| unsafe { list.insert_unique_sorted(NonNull::from(node)) })
+        .collect();
+    assert_eq!(inserted, vec![true, true, false, true]);
+    assert_eq!(list.count(), 3);
+
+    let mut values = vec![];
+    unsafe {
+        for node in list.iter() {
+            values.push(*node.as_ref().data());
+        }
+    }
+    assert_eq!(values, vec![1, 2, 3]);
+
+    unsafe {
+        for node in list.iter() {
+            if let Some(next) = node.as_ref().next() {
+                assert_eq!(next.as_ref().prev(), Some(node));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_double_list_splice_after() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut other = LinkedList::<DoubleNode<i32>>::new();
+    let mut nodes = [1, 2, 3, 4, 5].map(|v| {
+        let mut node = DoubleNode::<i32>::default();
+        *node.data_mut() = v;
+        node
+    });
+    let [n1, n2, n3, n4, n5] = &mut nodes;
+
+    list.push(NonNull::from(n5));
+    list.push(NonNull::from(n4));
+    list.push(NonNull::from(n1)); // list is 1 -> 4 -> 5
+    other.push(NonNull::from(n3));
+    other.push(NonNull::from(n2)); // other is 2 -> 3
+
+    unsafe {
+        list.splice_after(list.head(), other);
+    }
+    assert_eq!(list.count(), 5);
+
+    let mut values = vec![];
+    unsafe {
+        for node in list.iter() {
+            values.push(*node.as_ref().data());
+            // Both boundaries of the spliced range must point back correctly
+            if let Some(next) = node.as_ref().next() {
+                assert_eq!(next.as_ref().prev(), Some(node));
+            }
+        }
+        assert!(list.head().unwrap().as_ref().prev().is_none());
+    }
+    assert_eq!(values, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_double_list_repair_prev() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut node1 = DoubleNode::<i32>::new(1);
+    let mut node2 = DoubleNode::<i32>::new(2);
+    let mut node3 = DoubleNode::<i32>::new(3);
+    let (ptr1, ptr2, ptr3) = (
+        NonNull::from(&mut node1),
+        NonNull::from(&mut node2),
+        NonNull::from(&mut node3),
+    );
+
+    list.push(ptr3);
+    list.push(ptr2);
+    list.push(ptr1); // list is 1 -> 2 -> 3
+
+    unsafe {
+        // Corrupt the prev pointers
+        (*ptr3.as_ptr()).set_prev(Some(ptr3));
+        (*ptr1.as_ptr()).set_prev(Some(ptr2));
+
+        list.repair_prev();
+        assert!(ptr1.as_ref().prev().is_none());
+
+        // Walking back from the tail yields the reversed order
+        let mut values = vec![];
+        let mut current = Some(ptr3);
+        while let Some(node) = current {
+            values.push(*node.as_ref().data());
+            current = node.as_ref().prev();
+        }
+        assert_eq!(values, vec![3, 2, 1]);
+    }
+}
+
+#[test]
+fn test_double_list_push_back() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut node1 = DoubleNode::<i32>::new(1);
+    let mut node2 = DoubleNode::<i32>::new(2);
+    let mut node3 = DoubleNode::<i32>::new(3);
+    let (ptr1, ptr2, ptr3) = (
+        NonNull::from(&mut node1),
+        NonNull::from(&mut node2),
+        NonNull::from(&mut node3),
+    );
+
+    list.push_back(ptr1);
+    list.push_back(ptr2);
+    list.push_back(ptr3);
+    assert_eq!(list.tail(), Some(ptr3));
+    assert!(list.prev_links_consistent());
+    unsafe {
+        assert_eq!(ptr3.as_ref().prev(), Some(ptr2));
+    }
+
+    // The tail detaches through its prev pointer and the tail moves back
+    unsafe {
+        assert_eq!(list.quick_remove(ptr3, None), Some(ptr3));
+    }
+    assert_eq!(list.tail(), Some(ptr2));
+    assert!(list.prev_links_consistent());
+
+    list.push_back(ptr3);
+    let values = unsafe {
+        list.iter()
+            .map(|n| *n.as_ref().data())
+            .collect::<vec::Vec<_>>()
+    };
+    assert_eq!(values, vec![1, 2, 3]);
+    assert!(list.prev_links_consistent());
+
+    while list.pop().is_some() {}
+    assert_eq!(list.tail(), None);
+}
+
+#[test]
+fn test_double_list_pop_back() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    assert!(list.pop_back().is_none());
+
+    let mut node1 = DoubleNode::<i32>::new(1);
+    let mut node2 = DoubleNode::<i32>::new(2);
+    let mut node3 = DoubleNode::<i32>::new(3);
+    let (ptr1, ptr2, ptr3) = (
+        NonNull::from(&mut node1),
+        NonNull::from(&mut node2),
+        NonNull::from(&mut node3),
+    );
+
+    list.push_back(ptr2);
+    list.push_back(ptr3);
+    list.push(ptr1); // list is 1 -> 2 -> 3
+
+    assert_eq!(list.pop_back(), Some(ptr3));
+    assert_eq!(list.tail(), Some(ptr2));
+    assert_eq!(list.count(), 2);
+    unsafe {
+        assert!(ptr2.as_ref().next().is_none());
+    }
+    assert!(list.prev_links_consistent());
+
+    // Both ends stay usable after popping from the back
+    list.push_back(ptr3);
+    assert_eq!(list.pop(), Some(ptr1));
+    assert_eq!(list.pop_back(), Some(ptr3));
+    assert_eq!(list.pop_back(), Some(ptr2));
+
+    // Popping the last node leaves the list empty
+    assert!(list.is_empty());
+    assert_eq!(list.head(), None);
+    assert_eq!(list.tail(), None);
+    assert!(list.pop_back().is_none());
+
+    list.push_back(ptr1);
+    assert_eq!(list.head(), Some(ptr1));
+    assert_eq!(list.tail(), Some(ptr1));
+}
+
+#[test]
+fn test_double_list_prev_links_consistent() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    assert!(list.prev_links_consistent());
+
+    let mut node1 = DoubleNode::<i32>::new(1);
+    let mut node2 = DoubleNode::<i32>::new(2);
+    let mut node3 = DoubleNode::<i32>::new(3);
+    let ptr1 = NonNull::from(&mut node1);
+    let ptr3 = NonNull::from(&mut node3);
+
+    list.push(ptr3);
+    list.push(NonNull::from(&mut node2));
+    list.push(ptr1); // list is 1 -> 2 -> 3
+    assert!(list.prev_links_consistent());
+
+    unsafe {
+        // Break the prev pointer of the tail
+        (*ptr3.as_ptr()).set_prev(Some(ptr1));
+    }
+    assert!(!list.prev_links_consistent());
+
+    unsafe {
+        list.repair_prev();
+    }
+    assert!(list.prev_links_consistent());
+}
+
+#[test]
+fn test_double_node_debug_and_clone() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut node1 = DoubleNode::new(1);
+    let mut node2 = DoubleNode::new(2);
+    list.push(NonNull::from(&mut node1));
+    list.push(NonNull::from(&mut node2));
+
+    let debug = std::format!("{:?}", node1);
+    assert!(debug.starts_with("DoubleNode"));
+    assert!(debug.contains("data: 1"));
+
+    // The clone is unlinked while the original stays in the list
+    let copy = node1.clone();
+    assert_eq!(*copy.data(), 1);
+    assert!(copy.next().is_none());
+    assert!(copy.prev().is_none());
+    assert!(node1.prev().is_some());
+    assert_eq!(list.count(), 2);
+}
+
+#[test]
+fn test_double_list_retain_map() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut nodes = [5, 1, 8, 2, 6].map(DoubleNode::new);
+    for node in nodes.iter_mut().rev() {
+        list.push(NonNull::from(node));
+    }
+
+    // Double every value and drop those exceeding 10
+    let removed = unsafe {
+        list.retain_map(|v| {
+            *v *= 2;
+            *v <= 10
+        })
+    };
+
+    let data = |list: &LinkedList<DoubleNode<i32>>| unsafe {
+        list.iter().map(|n| *n.as_ref().data()).collect::<vec::Vec<_>>()
+    };
+    assert_eq!(data(&list), vec![10, 2, 4]);
+    assert_eq!(data(&removed), vec![16, 12]);
+    assert_eq!(list.count(), 3);
+    assert_eq!(removed.count(), 2);
+
+    // The prev pointers of the survivors skip the removed nodes
+    unsafe {
+        let survivors = list.iter().collect::<vec::Vec<_>>();
+        assert!(survivors[0].as_ref().prev().is_none());
+        assert_eq!(survivors[1].as_ref().prev(), Some(survivors[0]));
+        assert_eq!(survivors[2].as_ref().prev(), Some(survivors[1]));
+    }
+}
+
+#[test]
+fn test_double_nodes_from() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut nodes = nodes_from([1, 2, 3]);
+    for node in nodes.iter_mut() {
+        list.push(NonNull::from(node));
+    }
+
+    let data = unsafe { list.iter().map(|n| *n.as_ref().data()).collect::<vec::Vec<_>>() };
+    assert_eq!(data, vec![3, 2, 1]);
+    assert_eq!(list.count(), 3);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Node links to itself")]
+fn test_double_push_head_again_panics() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut node = DoubleNode::<i32>::default();
+    list.push(NonNull::from(&mut node));
+    // Pushing the head again would make it its own successor
+    list.push(NonNull::from(&mut node));
+}
+
+#[test]
+fn test_double_validate() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut nodes = nodes_from([1, 2, 3]);
+    let ptrs = nodes.iter_mut().map(NonNull::from).collect::<vec::Vec<_>>();
+    for &ptr in &ptrs {
+        list.push_back(ptr);
+    }
+    assert_eq!(list.validate(), Ok(()));
+
+    unsafe {
+        // Break the prev pointer of the tail
+        (*ptrs[2].as_ptr()).set_prev(Some(ptrs[0]));
+        assert_eq!(
+            list.validate(),
+            Err(ListIntegrityError::BrokenPrevLink { index: 2 })
+        );
+        list.repair_prev();
+        assert_eq!(list.validate(), Ok(()));
+
+        // A loop is reported before the prev pointer it breaks
+        (*ptrs[2].as_ptr()).set_next(Some(ptrs[0]));
+        assert_eq!(list.validate(), Err(ListIntegrityError::Cycle));
+        (*ptrs[2].as_ptr()).set_next(None);
+    }
+    assert_eq!(list.validate(), Ok(()));
+}
+
+#[test]
+fn test_double_list_iter_rev() {
+    let mut list = LinkedList::<DoubleNode<i32>>::new();
+    let mut nodes = nodes_from([1, 2, 3, 4, 5]);
+    for node in nodes.iter_mut() {
+        list.push_back(NonNull::from(node));
+    }
+
+    let data = |nodes: vec::Vec<NonNull<DoubleNode<i32>>>| {
+        nodes
+            .iter()
+            .map(|n| unsafe { *n.as_ref().data() })
+            .collect::<vec::Vec<_>>()
+    };
+    let forward = data(unsafe { list.iter().collect() });
+    let mut backward = data(unsafe { list.iter().rev().collect() });
+    backward.reverse();
+    assert_eq!(forward, backward);
+
+    // Both ends meet on the middle node, which is yielded once
+    unsafe {
+        let mut iter = list.iter();
+        let mut taken = vec![];
+        while let Some(front) = iter.next() {
+            taken.push(*front.as_ref().data());
+            if let Some(back) = iter.next_back() {
+                taken.push(*back.as_ref().data());
+            }
+        }
+        assert_eq!(taken, vec![1, 5, 2, 4, 3]);
+        assert!(iter.next_back().is_none());
+    }
+
+    // An even number of nodes meets between two nodes
+    list.pop_back();
+    let taken = unsafe {
+        let mut iter = list.iter();
+        [
+            iter.next_back(),
+            iter.next(),
+            iter.next_back(),
+            iter.next(),
+            iter.next(),
+        ]
+    };
+    assert_eq!(
+        data(taken.iter().flatten().copied().collect()),
+        vec![4, 1, 3, 2]
+    );
+    assert!(taken[4].is_none());
+}
@@ -1,2 +1,6 @@
 mod single;
 mod double;
+mod alloc_only;
+mod pool;
+mod stack;
+mod timer;
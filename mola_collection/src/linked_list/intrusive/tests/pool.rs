@@ -0,0 +1,34 @@
+use alloc::vec::Vec;
+
+use crate::linked_list::intrusive::{pool::ObjectPool, traits::NodeWithData};
+
+#[test]
+fn test_pool_reuses_released_nodes() {
+    let mut pool = ObjectPool::<i32>::new();
+    let first = pool.acquire();
+    unsafe { pool.release(first) };
+    assert_eq!(pool.free_count(), 1);
+
+    for i in 0..100 {
+        let node = pool.acquire();
+        assert_eq!(node, first);
+        assert_eq!(pool.free_count(), 0);
+        unsafe {
+            *(*node.as_ptr()).data_mut() = i;
+            pool.release(node);
+        }
+    }
+
+    // Nodes are reused in LIFO order, and new ones are allocated when empty
+    let nodes = (0..3).map(|_| pool.acquire()).collect::<Vec<_>>();
+    assert_eq!(nodes[0], first);
+    assert_eq!(unsafe { *nodes[0].as_ref().data() }, 99);
+    assert!(nodes[1] != first && nodes[2] != nodes[1]);
+    for node in nodes.into_iter().rev() {
+        unsafe { pool.release(node) };
+    }
+    assert_eq!(pool.free_count(), 3);
+    let node = pool.acquire();
+    assert_eq!(node, first);
+    unsafe { pool.release(node) };
+}
@@ -5,9 +5,9 @@ use std::vec;
 use core::ptr::NonNull;
 
 use crate::linked_list::intrusive::{
-    list::LinkedList,
-    single::SingleNode,
-    traits::{List, NodeWithData},
+    list::{LinkedList, ListIntegrityError},
+    single::{SingleNode, nodes_from},
+    traits::{Link, List, NodeWithData},
 };
 
 #[test]
@@ -61,6 +61,78 @@ fn test_single_list_iter() {
     assert_eq!(values, vec![3, 2, 1]);
 }
 
+#[test]
+fn test_single_list_enumerate() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut node1 = SingleNode::<i32>::new(1);
+    let mut node2 = SingleNode::<i32>::new(2);
+    let mut node3 = SingleNode::<i32>::new(3);
+    let ptr1 = NonNull::from(&mut node1);
+
+    list.push(NonNull::from(&mut node3));
+    list.push(NonNull::from(&mut node2));
+    list.push(ptr1); // list is 1 -> 2 -> 3
+
+    let entries = unsafe {
+        list.enumerate()
+            .map(|(index, node)| (index, *node.as_ref().data()))
+            .collect::<vec::Vec<_>>()
+    };
+    assert_eq!(entries, vec![(0, 1), (1, 2), (2, 3)]);
+    assert_eq!(unsafe { list.enumerate().next() }, Some((0, ptr1)));
+}
+
+#[test]
+fn test_single_list_push_back() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut node1 = SingleNode::<i32>::new(1);
+    let mut node2 = SingleNode::<i32>::new(2);
+    let mut node3 = SingleNode::<i32>::new(3);
+    let (ptr1, ptr2, ptr3) = (
+        NonNull::from(&mut node1),
+        NonNull::from(&mut node2),
+        NonNull::from(&mut node3),
+    );
+    let values = |list: &LinkedList<SingleNode<i32>>| unsafe {
+        list.iter()
+            .map(|n| *n.as_ref().data())
+            .collect::<vec::Vec<_>>()
+    };
+
+    list.push_back(ptr1);
+    list.push_back(ptr2);
+    list.push_back(ptr3);
+    assert_eq!(values(&list), vec![1, 2, 3]);
+    assert_eq!(list.tail(), Some(ptr3));
+    assert_eq!(list.count(), 3);
+
+    // Popping the head keeps the tail
+    assert_eq!(list.pop(), Some(ptr1));
+    assert_eq!(list.tail(), Some(ptr3));
+
+    // Removing the tail moves it back
+    assert_eq!(list.remove(ptr3), Some(ptr3));
+    assert_eq!(list.tail(), Some(ptr2));
+
+    // Removing the last node empties the tail
+    unsafe {
+        assert_eq!(list.quick_remove(ptr2, None), Some(ptr2));
+    }
+    assert!(list.is_empty());
+    assert_eq!(list.tail(), None);
+
+    list.push_back(ptr1);
+    list.push(ptr3);
+    assert_eq!(values(&list), vec![3, 1]);
+    assert_eq!(list.tail(), Some(ptr1));
+    unsafe {
+        assert_eq!(list.quick_remove(ptr1, Some(ptr3)), Some(ptr1));
+    }
+    assert_eq!(list.tail(), Some(ptr3));
+    assert_eq!(list.pop(), Some(ptr3));
+    assert_eq!(list.tail(), None);
+}
+
 #[test]
 fn test_single_list_remove() {
     let mut list = LinkedList::<SingleNode<i32>>::new();
@@ -100,3 +172,294 @@ fn test_single_list_remove() {
     assert!(removed.is_some());
     assert!(list.is_empty());
 }
+
+#[test]
+fn test_single_list_insert_unique_sorted() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut nodes = [2, 1, 2, 3].map(|v| {
+        let mut node = SingleNode::<i32>::default();
+        *node.data_mut() = v;
+        node
+    });
+
+    let inserted: vec::Vec<bool> = nodes
+        .iter_mut()
+        .map(|node| unsafe { list.insert_unique_sorted(NonNull::from(node)) })
+        .collect();
+    assert_eq!(inserted, vec![true, true, false, true]);
+    assert_eq!(list.count(), 3);
+
+    let mut values = vec![];
+    unsafe {
+        for node in list.iter() {
+            values.push(*node.as_ref().data());
+        }
+    }
+    assert_eq!(values, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_single_list_splice_after() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut other = LinkedList::<SingleNode<i32>>::new();
+    let mut nodes = [1, 2, 3, 4, 5].map(|v| {
+        let mut node = SingleNode::<i32>::default();
+        *node.data_mut() = v;
+        node
+    });
+    let [n1, n2, n3, n4, n5] = &mut nodes;
+
+    list.push(NonNull::from(n5));
+    list.push(NonNull::from(&mut *n4));
+    list.push(NonNull::from(n1)); // list is 1 -> 4 -> 5
+    other.push(NonNull::from(n3));
+    other.push(NonNull::from(n2)); // other is 2 -> 3
+
+    unsafe {
+        list.splice_after(list.head(), other);
+        // Splicing an empty list is a no-op
+        list.splice_after(Some(NonNull::from(n4)), LinkedList::new());
+    }
+    assert_eq!(list.count(), 5);
+
+    let mut values = vec![];
+    unsafe {
+        for node in list.iter() {
+            values.push(*node.as_ref().data());
+        }
+    }
+    assert_eq!(values, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_single_list_pop_data() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut nodes = [1, 2, 3].map(|v| {
+        let mut node = SingleNode::<i32>::default();
+        *node.data_mut() = v;
+        node
+    });
+    for node in nodes.iter_mut() {
+        list.push(NonNull::from(node));
+    }
+
+    assert_eq!(list.pop_data(), Some(3));
+    assert_eq!(list.pop_data(), Some(2));
+    assert_eq!(list.pop_data(), Some(1));
+    assert_eq!(list.pop_data(), None);
+    assert!(list.is_empty());
+    assert!(nodes.iter().all(|node| node.next().is_none()));
+}
+
+#[test]
+fn test_single_list_pop_with_data() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut node1 = SingleNode::<i32>::new(1);
+    let mut node2 = SingleNode::<i32>::new(2);
+
+    list.push(NonNull::from(&mut node1));
+    list.push(NonNull::from(&mut node2));
+
+    unsafe {
+        let (node, data) = list.pop_with_data().unwrap();
+        assert_eq!(node, NonNull::from(&mut node2));
+        assert_eq!(*data, 2);
+
+        let (_, data) = list.pop_with_data().unwrap();
+        assert_eq!(*data, 1);
+        assert!(list.pop_with_data().is_none());
+    }
+}
+
+#[test]
+fn test_single_list_len_by_traversal() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut nodes = [1, 2, 3, 4].map(SingleNode::<i32>::new);
+    assert_eq!(list.len_by_traversal(), 0);
+
+    for node in nodes.iter_mut() {
+        list.push(NonNull::from(node));
+        assert_eq!(list.len_by_traversal(), list.count());
+    }
+    list.remove(NonNull::from(&mut nodes[1]));
+    assert_eq!(list.len_by_traversal(), list.count());
+    list.remove(NonNull::from(&mut nodes[3]));
+    list.pop();
+    assert_eq!(list.len_by_traversal(), 1);
+    assert_eq!(list.len_by_traversal(), list.count());
+}
+
+#[test]
+fn test_single_node_debug_and_clone() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut node1 = SingleNode::new(1);
+    let mut node2 = SingleNode::new(2);
+    list.push(NonNull::from(&mut node1));
+    list.push(NonNull::from(&mut node2));
+
+    assert!(std::format!("{:?}", node2).contains("data: 2"));
+
+    let copy = node2.clone();
+    assert_eq!(*copy.data(), 2);
+    assert!(copy.next().is_none());
+    assert!(node2.next().is_some());
+}
+
+#[test]
+fn test_single_nodes_from() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut nodes = nodes_from([1, 2, 3]);
+    for node in nodes.iter_mut() {
+        list.push(NonNull::from(node));
+    }
+
+    let data = unsafe { list.iter().map(|n| *n.as_ref().data()).collect::<vec::Vec<_>>() };
+    assert_eq!(data, vec![3, 2, 1]);
+    assert_eq!(list.count(), 3);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Node links to itself")]
+fn test_single_push_head_again_panics() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut node = SingleNode::<i32>::default();
+    list.push(NonNull::from(&mut node));
+    // Pushing the head again would make it its own successor
+    list.push(NonNull::from(&mut node));
+}
+
+#[test]
+fn test_single_remove_verified() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut nodes = nodes_from([1, 2, 3]);
+    let [ptr1, ptr2, ptr3] = nodes.each_mut().map(NonNull::from);
+    list.push_back(ptr1);
+    list.push_back(ptr2);
+    list.push_back(ptr3);
+
+    // Correct parent
+    assert_eq!(list.remove_verified(ptr3, Some(ptr2)), Some(ptr3));
+    assert_eq!(list.tail(), Some(ptr2));
+
+    // The head has no parent
+    assert_eq!(list.remove_verified(ptr1, None), Some(ptr1));
+    assert_eq!(list.head(), Some(ptr2));
+
+    // Nodes outside the list are not removed
+    assert_eq!(list.remove_verified(ptr3, Some(ptr2)), None);
+    assert_eq!(list.count(), 1);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Parent does not precede the node")]
+fn test_single_remove_verified_wrong_parent_panics() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut nodes = nodes_from([1, 2, 3]);
+    let [ptr1, ptr2, ptr3] = nodes.each_mut().map(NonNull::from);
+    list.push_back(ptr1);
+    list.push_back(ptr2);
+    list.push_back(ptr3);
+
+    list.remove_verified(ptr3, Some(ptr1));
+}
+
+#[test]
+fn test_single_count_matching() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let values = [3, 8, 1, 4, 4, 9, 0, 7];
+    let mut nodes = nodes_from(values);
+    for node in nodes.iter_mut() {
+        list.push(NonNull::from(node));
+    }
+
+    let evens = values.iter().filter(|v| *v % 2 == 0).count();
+    assert_eq!(list.count_matching(|v| v % 2 == 0), evens);
+    assert_eq!(list.count_matching(|v| *v > 100), 0);
+    assert_eq!(list.count_matching(|_| true), values.len());
+    assert_eq!(LinkedList::<SingleNode<i32>>::new().count_matching(|_| true), 0);
+}
+
+#[test]
+fn test_single_quick_remove_without_parent() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut nodes = nodes_from([1, 2, 3, 4]);
+    for node in nodes.iter_mut() {
+        list.push(NonNull::from(node));
+    }
+
+    // The list is 4, 3, 2, 1; remove a middle node and then the tail
+    unsafe {
+        let removed = list.quick_remove(NonNull::from(&mut nodes[1]), None);
+        assert_eq!(removed, Some(NonNull::from(&mut nodes[1])));
+        list.quick_remove(NonNull::from(&mut nodes[0]), None);
+    }
+    let data = unsafe { list.iter().map(|n| *n.as_ref().data()).collect::<vec::Vec<_>>() };
+    assert_eq!(data, vec![4, 3]);
+    assert_eq!(list.count(), 2);
+    assert_eq!(list.len_by_traversal(), 2);
+}
+
+#[test]
+fn test_single_validate() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    assert_eq!(list.validate(), Ok(()));
+
+    let mut nodes = nodes_from([1, 2, 3, 4]);
+    let ptrs = nodes.iter_mut().map(NonNull::from).collect::<vec::Vec<_>>();
+    for &ptr in &ptrs[..3] {
+        list.push_back(ptr);
+    }
+    assert_eq!(list.validate(), Ok(()));
+
+    unsafe {
+        // A node linked after the tail is not counted
+        (*ptrs[2].as_ptr()).set_next(Some(ptrs[3]));
+        assert_eq!(
+            list.validate(),
+            Err(ListIntegrityError::CountMismatch {
+                expected: 3,
+                found: 4
+            })
+        );
+
+        // The tail looping back to the head
+        (*ptrs[2].as_ptr()).set_next(Some(ptrs[0]));
+        assert_eq!(list.validate(), Err(ListIntegrityError::Cycle));
+
+        // Another node replacing the tail keeps the count
+        (*ptrs[1].as_ptr()).set_next(Some(ptrs[3]));
+        assert_eq!(list.validate(), Err(ListIntegrityError::TailMismatch));
+
+        (*ptrs[1].as_ptr()).set_next(Some(ptrs[2]));
+        (*ptrs[2].as_ptr()).set_next(None);
+    }
+    assert_eq!(list.validate(), Ok(()));
+}
+
+#[test]
+fn test_single_iter_mut() {
+    let mut list = LinkedList::<SingleNode<i32>>::new();
+    let mut nodes = nodes_from([1, 2, 3, 4]);
+    for node in nodes.iter_mut() {
+        list.push_back(NonNull::from(node));
+    }
+
+    unsafe {
+        for node in list.iter_mut() {
+            *node.data_mut() += 1;
+        }
+    }
+    let data = unsafe {
+        list.iter()
+            .map(|n| *n.as_ref().data())
+            .collect::<vec::Vec<_>>()
+    };
+    assert_eq!(data, vec![2, 3, 4, 5]);
+
+    // Every node is yielded exactly once
+    assert_eq!(unsafe { list.iter_mut().count() }, list.count());
+    let mut empty = LinkedList::<SingleNode<i32>>::new();
+    assert!(unsafe { empty.iter_mut() }.next().is_none());
+}
@@ -0,0 +1,25 @@
+use core::ptr::NonNull;
+
+use crate::linked_list::intrusive::{single::SingleNode, stack::Stack, traits::NodeWithData};
+
+#[test]
+fn test_stack_push_pop_peek() {
+    let mut stack = Stack::<i32>::new();
+    let mut nodes = [1, 2, 3].map(SingleNode::new);
+    assert!(stack.peek().is_none());
+
+    for node in nodes.iter_mut() {
+        let value = *node.data();
+        stack.push(NonNull::from(node));
+        assert_eq!(unsafe { *stack.peek().unwrap().as_ref().data() }, value);
+    }
+    assert_eq!(stack.len(), 3);
+
+    for expected in [3, 2, 1] {
+        let top = stack.peek().unwrap();
+        assert_eq!(stack.pop(), Some(top));
+        assert_eq!(unsafe { *top.as_ref().data() }, expected);
+    }
+    assert!(stack.is_empty());
+    assert!(stack.pop().is_none());
+}
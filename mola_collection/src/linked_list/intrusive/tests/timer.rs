@@ -0,0 +1,65 @@
+extern crate std;
+
+use std::vec;
+use std::vec::Vec;
+
+use core::ptr::NonNull;
+
+use crate::linked_list::intrusive::{
+    list::LinkedList,
+    timer::{TimerEntry, TimerList, TimerNode},
+    traits::{List, NodeWithData},
+};
+
+/// Collect the payloads of expired timers in list order.
+fn payloads(list: &LinkedList<TimerNode<&'static str>>) -> Vec<&'static str> {
+    unsafe { list.iter().map(|n| *n.as_ref().data().data()).collect() }
+}
+
+#[test]
+fn test_timer_advance_expires_in_order() {
+    let mut timers = TimerList::with_slots(8);
+    let mut nodes =
+        ["a", "b", "c", "d", "e", "f"].map(|name| TimerNode::new(TimerEntry::new(name)));
+    let deadlines = [5, 3, 20, 3, 12, 7];
+    for (node, deadline) in nodes.iter_mut().zip(deadlines) {
+        timers.insert(NonNull::from(node), deadline);
+    }
+    assert_eq!(timers.len(), 6);
+
+    assert!(timers.advance(2).is_empty());
+    assert_eq!(payloads(&timers.advance(5)), vec!["b", "d", "a"]);
+    assert_eq!(timers.len(), 3);
+
+    // Cancelled timers never expire
+    unsafe { timers.cancel(NonNull::from(&mut nodes[5])) };
+
+    // Skipping more than a revolution still orders across rounds
+    assert_eq!(payloads(&timers.advance(40)), vec!["e", "c"]);
+    assert!(timers.is_empty());
+    assert_eq!(timers.now(), 40);
+}
+
+#[test]
+fn test_timer_overdue_and_same_slot() {
+    let mut timers = TimerList::with_slots(4);
+    let mut nodes =
+        ["late", "far", "near", "tie"].map(|name| TimerNode::new(TimerEntry::new(name)));
+    timers.advance(10);
+
+    // All four share a slot, the overdue timer being scanned at the next tick
+    timers.insert(NonNull::from(&mut nodes[0]), 3);
+    timers.insert(NonNull::from(&mut nodes[1]), 15);
+    timers.insert(NonNull::from(&mut nodes[2]), 11);
+    timers.insert(NonNull::from(&mut nodes[3]), 11);
+
+    assert_eq!(payloads(&timers.advance(11)), vec!["late", "near", "tie"]);
+    assert_eq!(timers.len(), 1);
+    assert!(timers.advance(14).is_empty());
+    let expired = timers.advance(15);
+    assert_eq!(payloads(&expired), vec!["far"]);
+    assert_eq!(
+        unsafe { expired.head().unwrap().as_ref().data().deadline() },
+        15
+    );
+}
@@ -0,0 +1,275 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ptr::NonNull;
+
+use super::double::DoubleNode;
+use super::list::LinkedList;
+use super::traits::{Link, LinkWithPrev, List, NodeWithData};
+
+// Default number of slots of a timer wheel. Must be a power of two.
+const DEFAULT_SLOTS: usize = 64;
+
+/// The data of a timer node: a deadline and a user payload.
+///
+/// The deadline and slot are written by [`TimerList::insert`], so a timer
+/// needs no allocation beyond its own node.
+#[derive(Debug, Clone, Default)]
+pub struct TimerEntry<T> {
+    deadline: u64,
+    slot: usize,
+    data: T,
+}
+
+impl<T> TimerEntry<T> {
+    /// Create a new timer entry holding the given data.
+    pub fn new(data: T) -> Self {
+        Self {
+            deadline: 0,
+            slot: 0,
+            data,
+        }
+    }
+
+    /// Get the deadline the timer was last inserted with.
+    pub fn deadline(&self) -> u64 {
+        self.deadline
+    }
+
+    /// Get a reference to the payload.
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Get a mutable reference to the payload.
+    pub fn data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    /// Consume the entry and return the payload.
+    pub fn into_data(self) -> T {
+        self.data
+    }
+}
+
+/// A node of a [`TimerList`].
+pub type TimerNode<T> = DoubleNode<TimerEntry<T>>;
+
+/// The chain of timers hashed to one slot, in insertion order.
+struct Slot<T> {
+    list: LinkedList<TimerNode<T>>,
+    tail: Option<NonNull<TimerNode<T>>>,
+}
+
+impl<T> Slot<T> {
+    /// Link a node at the end of the chain.
+    ///
+    /// # Safety
+    ///
+    /// The node must be alive and not linked into any list.
+    unsafe fn push_back(&mut self, node: NonNull<TimerNode<T>>) {
+        unsafe {
+            if let Some(tail) = self.tail {
+                self.list.insert_after(tail, node);
+            } else {
+                self.list.push(node);
+            }
+        }
+        self.tail = Some(node);
+    }
+
+    /// Unlink a node from the chain.
+    ///
+    /// # Safety
+    ///
+    /// The node must be linked into this chain.
+    unsafe fn unlink(&mut self, node: NonNull<TimerNode<T>>) {
+        unsafe {
+            if self.tail == Some(node) {
+                self.tail = node.as_ref().prev();
+            }
+            self.list.quick_remove(node, None);
+        }
+    }
+}
+
+/// A hashed timer wheel threading its timers through intrusive lists.
+///
+/// Time is measured in abstract ticks. A timer is hashed to the slot of its
+/// deadline modulo the number of slots, and each slot chains its timers in
+/// an intrusive doubly linked list, so inserting and cancelling are `O(1)`.
+/// Advancing the wheel scans the slots of the elapsed ticks, at most once
+/// each, and only expires the timers whose deadline has passed.
+///
+/// Timers with a deadline at or before the current tick expire on the next
+/// call to [`advance`](Self::advance).
+pub struct TimerList<T> {
+    slots: Box<[Slot<T>]>,
+    now: u64,
+    len: usize,
+}
+
+impl<T> TimerList<T> {
+    /// Create a new timer wheel with the default number of slots.
+    pub fn new() -> Self {
+        Self::with_slots(DEFAULT_SLOTS)
+    }
+
+    /// Create a new timer wheel with a custom number of slots.
+    ///
+    /// More slots make advancing cheaper when timers are far apart.
+    ///
+    /// # Arguments
+    /// * `slots` - The number of slots (must be a power of two)
+    ///
+    /// # Returns
+    /// A new timer wheel at tick zero
+    ///
+    /// # Panics
+    /// Panics if `slots` is not a power of two
+    pub fn with_slots(slots: usize) -> Self {
+        assert!(
+            slots.is_power_of_two(),
+            "Number of slots must be a power of two"
+        );
+        let slots = (0..slots)
+            .map(|_| Slot {
+                list: LinkedList::new(),
+                tail: None,
+            })
+            .collect::<Vec<_>>();
+        Self {
+            slots: slots.into_boxed_slice(),
+            now: 0,
+            len: 0,
+        }
+    }
+
+    /// Get the tick the wheel was last advanced to.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Get the number of pending timers.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if there are no pending timers.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the slot scanned at the given tick.
+    fn slot_index(&self, tick: u64) -> usize {
+        tick as usize & (self.slots.len() - 1)
+    }
+
+    /// Schedule a timer.
+    ///
+    /// The node must outlive its time in the wheel and must not be linked
+    /// into any other list.
+    ///
+    /// # Arguments
+    /// * `node` - The timer node
+    /// * `deadline` - The tick at which the timer expires
+    pub fn insert(&mut self, node: NonNull<TimerNode<T>>, deadline: u64) {
+        // Overdue timers are scanned at the very next tick
+        let slot = self.slot_index(deadline.max(self.now + 1));
+        unsafe {
+            let entry = (*node.as_ptr()).data_mut();
+            entry.deadline = deadline;
+            entry.slot = slot;
+            self.slots[slot].push_back(node);
+        }
+        self.len += 1;
+    }
+
+    /// Cancel a pending timer.
+    ///
+    /// # Safety
+    ///
+    /// The node must be a pending timer of this wheel.
+    pub unsafe fn cancel(&mut self, node: NonNull<TimerNode<T>>) {
+        unsafe {
+            let slot = node.as_ref().data().slot;
+            self.slots[slot].unlink(node);
+        }
+        self.len -= 1;
+    }
+
+    /// Advance the wheel to the given tick and collect the expired timers.
+    ///
+    /// # Arguments
+    /// * `now` - The current tick, ignored if not after the previous one
+    ///
+    /// # Returns
+    /// The timers whose deadline is at or before `now`, ordered by deadline
+    /// and then by insertion order
+    pub fn advance(&mut self, now: u64) -> LinkedList<TimerNode<T>> {
+        let mut expired = LinkedList::new();
+        if now <= self.now {
+            return expired;
+        }
+
+        let mut tail: Option<NonNull<TimerNode<T>>> = None;
+        let ticks = (now - self.now).min(self.slots.len() as u64);
+        for tick in self.now + 1..=self.now + ticks {
+            let slot = self.slot_index(tick);
+            let mut current = self.slots[slot].list.head();
+            while let Some(node) = current {
+                unsafe {
+                    current = node.as_ref().next();
+                    let deadline = node.as_ref().data().deadline;
+                    if deadline > now {
+                        continue;
+                    }
+                    self.slots[slot].unlink(node);
+                    insert_by_deadline(&mut expired, &mut tail, node);
+                }
+                self.len -= 1;
+            }
+        }
+        self.now = now;
+        expired
+    }
+}
+
+impl<T> Default for TimerList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Link an expired timer after the last one with a deadline not later.
+///
+/// Slots are scanned in tick order, so timers almost always arrive sorted
+/// and are appended at the tail. Only timers of later rounds, when the wheel
+/// skips a whole revolution, walk the list.
+///
+/// # Safety
+///
+/// The node must be alive and unlinked, and `tail` must be the last node
+/// of `list`.
+unsafe fn insert_by_deadline<T>(
+    list: &mut LinkedList<TimerNode<T>>,
+    tail: &mut Option<NonNull<TimerNode<T>>>,
+    node: NonNull<TimerNode<T>>,
+) {
+    unsafe {
+        let deadline = node.as_ref().data().deadline;
+        let mut prev = *tail;
+        while let Some(candidate) = prev {
+            if candidate.as_ref().data().deadline <= deadline {
+                break;
+            }
+            prev = candidate.as_ref().prev();
+        }
+        match prev {
+            Some(prev) => list.insert_after(prev, node),
+            None => list.push(node),
+        }
+        if prev == *tail {
+            *tail = Some(node);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use core::ptr::NonNull;
 
-use super::iter::LinkedListIter;
+use super::iter::{LinkedListIter, LinkedListIterMut};
 
 /// A trait for a linked list.
 pub trait List: Link + LinkWithPrev {
@@ -10,6 +10,14 @@ pub trait List: Link + LinkWithPrev {
     /// Set the head of the linked list
     fn set_head(&mut self, head: Option<NonNull<Self::Target>>);
 
+    /// Get the tail of the linked list, if the list keeps track of it
+    ///
+    /// The default returns `None`, in which case iterating from the back
+    /// first walks the list from the head to find its last node.
+    fn tail(&self) -> Option<NonNull<Self::Target>> {
+        None
+    }
+
     /// Push a new node to the front of the linked list
     fn push(&mut self, node: NonNull<Self::Target>);
 
@@ -24,6 +32,10 @@ pub trait List: Link + LinkWithPrev {
     /// This method quickly removes a node with an optional parent pointer,
     /// this will directly detach the node from the linked list without checking if it exists.
     /// Usually you will need a custom structure(index) to prove that the node is in the linked list.
+    ///
+    /// Without a parent, a node that cannot detach on its own, such as a
+    /// singly linked node, is unlinked after scanning the list from the head
+    /// for its parent, which is `O(n)`.
     /// 
     /// # Safety
     /// 
@@ -46,6 +58,16 @@ pub trait List: Link + LinkWithPrev {
     {
         unsafe { LinkedListIter::new(self) }
     }
+
+    /// Get an iterator yielding mutable references to the nodes
+    /// # Safety
+    /// The nodes must not form a cycle, so that no node is yielded twice.
+    unsafe fn iter_mut<'a>(&'a mut self) -> LinkedListIterMut<'a, Self::Target, Self>
+    where
+        Self::Target: Node
+    {
+        unsafe { LinkedListIterMut::new(self) }
+    }
 }
 
 /// A trait for a link in a linked list.
@@ -71,11 +93,40 @@ pub trait LinkWithPrev: Link {
 
 /// A trait for a node in a linked list.
 pub trait Node: Link {
+    /// Whether [`detach`](Self::detach) can unlink the node without a parent.
+    ///
+    /// Only nodes linking back to their previous node can, the default is
+    /// false.
+    const DETACHES_WITHOUT_PARENT: bool = false;
+
+    /// Get the node linked before this one, if the node links back to it.
+    ///
+    /// Only nodes that can detach without a parent know it, the default
+    /// returns `None`.
+    fn prev_node(&self) -> Option<NonNull<Self::Target>> {
+        None
+    }
+
     /// Append the node to a linked list
     fn append_to<L>(&mut self, list: &mut L)
     where
         L: List<Target = Self>;
 
+    /// Insert the node right after `prev`
+    ///
+    /// # Safety
+    ///
+    /// `prev` must be a node that is already linked into a list, and this node
+    /// must not be linked into any list. It will update the neighbours of `prev`
+    /// to include this node.
+    ///
+    /// The default only updates the next pointers, nodes linking back to
+    /// their previous node must override it to update the previous pointers.
+    unsafe fn insert_after(&mut self, prev: &mut Self) {
+        self.set_next(prev.next());
+        prev.set_next(Some(NonNull::from(self).cast()));
+    }
+
     /// Detach the node from the linked list
     /// 
     /// # Safety
@@ -124,6 +124,10 @@ where
         self.inner.set_head(head.map(|n| n.cast()));
     }
 
+    fn tail(&self) -> Option<NonNull<M>> {
+        self.inner.tail().map(|n| n.cast())
+    }
+
     fn push(&mut self, node: NonNull<M>) {
         self.inner.push(node.cast());
     }
@@ -42,3 +42,4 @@
 //! }
 //! ```
 pub mod intrusive;
+pub mod owned;
@@ -0,0 +1,68 @@
+use super::list::OwnedDoubleList;
+
+/// A double-ended queue backed by an owned doubly linked list.
+///
+/// All push and pop operations are O(1) at both ends.
+pub struct OwnedDeque<T> {
+    list: OwnedDoubleList<T>,
+}
+
+impl<T> OwnedDeque<T> {
+    /// Creates a new, empty deque.
+    pub const fn new() -> Self {
+        Self {
+            list: OwnedDoubleList::new(),
+        }
+    }
+
+    /// Push a value to the front of the deque
+    pub fn push_front(&mut self, data: T) {
+        self.list.push(data);
+    }
+
+    /// Push a value to the back of the deque
+    pub fn push_back(&mut self, data: T) {
+        self.list.push_back(data);
+    }
+
+    /// Pop a value from the front of the deque
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.list.pop()
+    }
+
+    /// Pop a value from the back of the deque
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    /// Get a reference to the value at the front of the deque
+    pub fn front(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    /// Get a reference to the value at the back of the deque
+    pub fn back(&self) -> Option<&T> {
+        self.list.back()
+    }
+
+    /// Get the number of values in the deque
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Check if the deque is empty
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Get an iterator over the values of the deque, from front to back
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.list.iter()
+    }
+}
+
+impl<T> Default for OwnedDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
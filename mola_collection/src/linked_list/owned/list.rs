@@ -0,0 +1,323 @@
+use alloc::boxed::Box;
+use core::ptr::NonNull;
+
+use crate::linked_list::intrusive::{
+    double::DoubleNode,
+    list::LinkedList,
+    single::SingleNode,
+    traits::{LinkWithPrev, List, NodeWithData},
+};
+
+/// A finalizer invoked on every value still in the list when it is dropped.
+type Finalizer<T> = Box<dyn FnMut(&mut T) + Send>;
+
+/// A singly linked list that owns its nodes.
+pub struct OwnedList<T> {
+    list: LinkedList<SingleNode<T>>,
+    finalizer: Option<Finalizer<T>>,
+}
+
+impl<T> OwnedList<T> {
+    /// Creates a new, empty list.
+    pub const fn new() -> Self {
+        Self {
+            list: LinkedList::new(),
+            finalizer: None,
+        }
+    }
+
+    /// Creates a new, empty list with a finalizer.
+    ///
+    /// When the list is dropped, `f` is called once on every value remaining
+    /// in the list, before the value itself is dropped. Popped values are not
+    /// passed to the finalizer.
+    pub fn with_finalizer<F>(f: F) -> Self
+    where
+        F: FnMut(&mut T) + Send + 'static,
+    {
+        Self {
+            list: LinkedList::new(),
+            finalizer: Some(Box::new(f)),
+        }
+    }
+
+    /// Push a value to the front of the list
+    pub fn push(&mut self, data: T) {
+        let node = NonNull::from(Box::leak(Box::new(SingleNode::new(data))));
+        self.list.push(node);
+    }
+
+    /// Push a value to the back of the list
+    pub fn push_back(&mut self, data: T) {
+        let node = NonNull::from(Box::leak(Box::new(SingleNode::new(data))));
+        self.list.push_back(node);
+    }
+
+    /// Pop a value from the front of the list
+    pub fn pop(&mut self) -> Option<T> {
+        self.list
+            .pop()
+            .map(|node| unsafe { Box::from_raw(node.as_ptr()) }.into_data())
+    }
+
+    /// Get a reference to the value at the front of the list
+    pub fn front(&self) -> Option<&T> {
+        self.list.head().map(|node| unsafe { node.as_ref() }.data())
+    }
+
+    /// Get a mutable reference to the value at the front of the list
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.list
+            .head()
+            .map(|node| unsafe { &mut *node.as_ptr() }.data_mut())
+    }
+
+    /// Get a reference to the value at the back of the list
+    pub fn back(&self) -> Option<&T> {
+        self.list.tail().map(|node| unsafe { node.as_ref() }.data())
+    }
+
+    /// Get a mutable reference to the value at the back of the list
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.list
+            .tail()
+            .map(|node| unsafe { &mut *node.as_ptr() }.data_mut())
+    }
+
+    /// Get the number of values in the list
+    pub fn len(&self) -> usize {
+        self.list.count()
+    }
+
+    /// Check if the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Get an iterator over the values of the list, from front to back
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        unsafe { self.list.iter() }.map(|node| unsafe { node.as_ref() }.data())
+    }
+
+    /// Consume the list and build a new list by applying `f` to every value.
+    ///
+    /// The values keep their order, and the nodes of this list are freed as
+    /// they are consumed.
+    pub fn map<U, F>(mut self, mut f: F) -> OwnedList<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        let mut mapped = OwnedList::new();
+        let mut tail = None;
+        while let Some(data) = self.pop() {
+            let node = NonNull::from(Box::leak(Box::new(SingleNode::new(f(data)))));
+            match tail {
+                Some(tail) => unsafe { mapped.list.insert_after(tail, node) },
+                None => mapped.list.push(node),
+            }
+            tail = Some(node);
+        }
+        mapped
+    }
+}
+
+impl<T> Default for OwnedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for OwnedList<T> {
+    fn drop(&mut self) {
+        while let Some(mut data) = self.pop() {
+            if let Some(finalizer) = self.finalizer.as_mut() {
+                finalizer(&mut data);
+            }
+        }
+    }
+}
+
+// Safety: The finalizer is only reachable through `&mut self`, so sharing the
+// list across threads only shares the values.
+unsafe impl<T: Sync> Sync for OwnedList<T> {}
+
+/// A singly linked list that boxes its nodes.
+///
+/// A thin wrapper around [`OwnedList`] naming its operations after the end
+/// of the list they act on. Values can be pushed at both ends in O(1), and
+/// popped from the front.
+pub struct BoxLinkedList<T> {
+    list: OwnedList<T>,
+}
+
+impl<T> BoxLinkedList<T> {
+    /// Creates a new, empty list.
+    pub const fn new() -> Self {
+        Self {
+            list: OwnedList::new(),
+        }
+    }
+
+    /// Push a value to the front of the list
+    pub fn push_front(&mut self, data: T) {
+        self.list.push(data);
+    }
+
+    /// Push a value to the back of the list
+    pub fn push_back(&mut self, data: T) {
+        self.list.push_back(data);
+    }
+
+    /// Pop a value from the front of the list
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.list.pop()
+    }
+
+    /// Get a reference to the value at the front of the list
+    pub fn front(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    /// Get a mutable reference to the value at the front of the list
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.list.front_mut()
+    }
+
+    /// Get a reference to the value at the back of the list
+    pub fn back(&self) -> Option<&T> {
+        self.list.back()
+    }
+
+    /// Get a mutable reference to the value at the back of the list
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.list.back_mut()
+    }
+
+    /// Get the number of values in the list
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Check if the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Get an iterator over the values of the list, from front to back
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.list.iter()
+    }
+}
+
+impl<T> Default for BoxLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A doubly linked list that owns its nodes.
+///
+/// The list keeps track of its tail, so values can be pushed and popped at
+/// both ends in O(1).
+pub struct OwnedDoubleList<T> {
+    list: LinkedList<DoubleNode<T>>,
+    tail: Option<NonNull<DoubleNode<T>>>,
+}
+
+impl<T> OwnedDoubleList<T> {
+    /// Creates a new, empty list.
+    pub const fn new() -> Self {
+        Self {
+            list: LinkedList::new(),
+            tail: None,
+        }
+    }
+
+    /// Push a value to the front of the list
+    pub fn push(&mut self, data: T) {
+        let node = NonNull::from(Box::leak(Box::new(DoubleNode::new(data))));
+        self.list.push(node);
+        if self.tail.is_none() {
+            self.tail = Some(node);
+        }
+    }
+
+    /// Push a value to the back of the list
+    pub fn push_back(&mut self, data: T) {
+        let Some(tail) = self.tail else {
+            return self.push(data);
+        };
+        let node = NonNull::from(Box::leak(Box::new(DoubleNode::new(data))));
+        unsafe { self.list.insert_after(tail, node) };
+        self.tail = Some(node);
+    }
+
+    /// Pop a value from the front of the list
+    pub fn pop(&mut self) -> Option<T> {
+        let node = self.list.pop()?;
+        if self.list.is_empty() {
+            self.tail = None;
+        }
+        Some(unsafe { Self::free(node) })
+    }
+
+    /// Pop a value from the back of the list
+    pub fn pop_back(&mut self) -> Option<T> {
+        let tail = self.tail?;
+        unsafe {
+            let prev = tail.as_ref().prev();
+            self.list.quick_remove(tail, prev);
+            self.tail = prev;
+            Some(Self::free(tail))
+        }
+    }
+
+    /// Get a reference to the value at the front of the list
+    pub fn front(&self) -> Option<&T> {
+        self.list.head().map(|node| unsafe { node.as_ref() }.data())
+    }
+
+    /// Get a reference to the value at the back of the list
+    pub fn back(&self) -> Option<&T> {
+        self.tail.map(|node| unsafe { node.as_ref() }.data())
+    }
+
+    /// Get the number of values in the list
+    pub fn len(&self) -> usize {
+        self.list.count()
+    }
+
+    /// Check if the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Get an iterator over the values of the list, from front to back
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        unsafe { self.list.iter() }.map(|node| unsafe { node.as_ref() }.data())
+    }
+
+    /// Free a node detached from the list and return its data.
+    ///
+    /// # Safety
+    ///
+    /// The node must have been allocated by this list and already detached.
+    unsafe fn free(node: NonNull<DoubleNode<T>>) -> T {
+        unsafe { Box::from_raw(node.as_ptr()) }.into_data()
+    }
+}
+
+impl<T> Default for OwnedDoubleList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for OwnedDoubleList<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+unsafe impl<T: Send> Send for OwnedDoubleList<T> {}
+unsafe impl<T: Sync> Sync for OwnedDoubleList<T> {}
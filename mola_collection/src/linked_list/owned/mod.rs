@@ -0,0 +1,20 @@
+//! # Owned Linked List
+//!
+//! This module provides safe linked lists built on top of the intrusive lists.
+//!
+//! Every element is stored in a boxed node that is owned by the list, so no
+//! `unsafe` code is needed to use them. The nodes are freed when they are
+//! popped or when the list is dropped.
+//!
+//! ## Core Components
+//!
+//! - [`list::OwnedList`]: A singly linked list based on [`SingleNode`](crate::linked_list::intrusive::single::SingleNode).
+//! - [`list::BoxLinkedList`]: A singly linked list with pushes at both ends and references to its values.
+//! - [`list::OwnedDoubleList`]: A doubly linked list based on [`DoubleNode`](crate::linked_list::intrusive::double::DoubleNode).
+//! - [`deque::OwnedDeque`]: A double-ended queue with O(1) operations at both ends.
+
+pub mod list;
+pub mod deque;
+
+#[cfg(test)]
+mod tests;
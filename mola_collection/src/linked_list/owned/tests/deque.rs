@@ -0,0 +1,79 @@
+extern crate std;
+
+use alloc::sync::Arc;
+use std::collections::VecDeque;
+use std::vec::Vec;
+
+use rand::{Rng, rng};
+
+use crate::linked_list::owned::deque::OwnedDeque;
+
+#[test]
+fn test_deque_push_pop_both_ends() {
+    let mut deque = OwnedDeque::new();
+    assert!(deque.is_empty());
+    assert_eq!(deque.pop_front(), None);
+    assert_eq!(deque.pop_back(), None);
+
+    deque.push_back(2);
+    deque.push_front(1);
+    deque.push_back(3); // deque is 1, 2, 3
+
+    assert_eq!(deque.len(), 3);
+    assert_eq!(deque.front(), Some(&1));
+    assert_eq!(deque.back(), Some(&3));
+    assert_eq!(deque.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+
+    assert_eq!(deque.pop_back(), Some(3));
+    assert_eq!(deque.pop_front(), Some(1));
+    assert_eq!(deque.pop_back(), Some(2));
+    assert!(deque.is_empty());
+    assert_eq!(deque.back(), None);
+
+    // The deque is reusable after being emptied from the back
+    deque.push_back(4);
+    assert_eq!(deque.front(), Some(&4));
+    assert_eq!(deque.back(), Some(&4));
+}
+
+#[test]
+fn test_deque_matches_vec_deque() {
+    let mut rng = rng();
+    let mut deque = OwnedDeque::new();
+    let mut expected = VecDeque::new();
+
+    for i in 0..2000 {
+        match rng.random_range(0..4) {
+            0 => {
+                deque.push_front(i);
+                expected.push_front(i);
+            }
+            1 => {
+                deque.push_back(i);
+                expected.push_back(i);
+            }
+            2 => assert_eq!(deque.pop_front(), expected.pop_front()),
+            _ => assert_eq!(deque.pop_back(), expected.pop_back()),
+        }
+
+        assert_eq!(deque.len(), expected.len());
+        assert_eq!(deque.front(), expected.front());
+        assert_eq!(deque.back(), expected.back());
+    }
+
+    assert!(deque.iter().eq(expected.iter()));
+}
+
+#[test]
+fn test_deque_drop_frees_values() {
+    let value = Arc::new(());
+    {
+        let mut deque = OwnedDeque::new();
+        for _ in 0..10 {
+            deque.push_back(Arc::clone(&value));
+            deque.push_front(Arc::clone(&value));
+        }
+        assert_eq!(Arc::strong_count(&value), 21);
+    }
+    assert_eq!(Arc::strong_count(&value), 1);
+}
@@ -0,0 +1,119 @@
+extern crate std;
+
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::vec::Vec;
+
+use crate::linked_list::owned::list::{BoxLinkedList, OwnedList};
+
+#[test]
+fn test_list_push_pop() {
+    let mut list = OwnedList::new();
+    assert!(list.is_empty());
+
+    list.push(1);
+    list.push(2);
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.front(), Some(&2));
+
+    *list.front_mut().unwrap() = 20;
+    assert_eq!(list.pop(), Some(20));
+    assert_eq!(list.pop(), Some(1));
+    assert_eq!(list.pop(), None);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn test_list_push_back() {
+    let mut list = OwnedList::new();
+    assert_eq!(list.back(), None);
+
+    list.push_back(2);
+    list.push(1);
+    list.push_back(3);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(list.back(), Some(&3));
+
+    *list.back_mut().unwrap() = 30;
+    assert_eq!(list.pop(), Some(1));
+    assert_eq!(list.pop(), Some(2));
+    assert_eq!(list.back(), Some(&30));
+    assert_eq!(list.pop(), Some(30));
+    assert_eq!(list.back(), None);
+}
+
+#[test]
+fn test_list_map() {
+    let mut list = OwnedList::new();
+    for i in [3, 2, 1] {
+        list.push(i);
+    }
+
+    let mapped: OwnedList<String> = list.map(|v| v.to_string());
+    assert_eq!(mapped.len(), 3);
+    assert_eq!(mapped.iter().cloned().collect::<Vec<_>>(), ["1", "2", "3"]);
+}
+
+#[test]
+fn test_list_finalizer() {
+    let finalized = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&finalized);
+    let mut list = OwnedList::with_finalizer(move |v: &mut i32| {
+        counter.fetch_add(*v as usize, Ordering::SeqCst);
+    });
+    for i in [1, 10, 100] {
+        list.push(i);
+    }
+
+    // Popped values are handed out and never finalized
+    assert_eq!(list.pop(), Some(100));
+    assert_eq!(finalized.load(Ordering::SeqCst), 0);
+
+    drop(list);
+    assert_eq!(finalized.load(Ordering::SeqCst), 11);
+}
+
+#[test]
+fn test_box_list_both_ends() {
+    let mut list = BoxLinkedList::new();
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.back(), None);
+
+    list.push_back(2);
+    list.push_front(1);
+    list.push_back(3);
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.front(), Some(&1));
+    assert_eq!(list.back(), Some(&3));
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+
+    *list.front_mut().unwrap() = 10;
+    *list.back_mut().unwrap() = 30;
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [10, 2, 30]);
+
+    assert_eq!(list.pop_front(), Some(10));
+    assert_eq!(list.pop_front(), Some(2));
+    assert_eq!(list.back(), Some(&30));
+    assert_eq!(list.pop_front(), Some(30));
+    assert!(list.is_empty());
+    assert_eq!(list.back(), None);
+
+    // The tail is reset once the list is emptied
+    list.push_back(4);
+    assert_eq!(list.front(), Some(&4));
+    assert_eq!(list.back(), Some(&4));
+}
+
+#[test]
+fn test_box_list_drop_frees_values() {
+    let value = Arc::new(());
+    let mut list = BoxLinkedList::new();
+    for _ in 0..5 {
+        list.push_back(Arc::clone(&value));
+    }
+    assert_eq!(Arc::strong_count(&value), 6);
+
+    drop(list);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
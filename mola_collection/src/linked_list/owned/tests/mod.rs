@@ -0,0 +1,2 @@
+mod deque;
+mod list;
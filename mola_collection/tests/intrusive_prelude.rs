@@ -0,0 +1,30 @@
+use core::ptr::NonNull;
+
+use mola_collections::linked_list::intrusive::prelude::*;
+
+#[derive(Node, Default)]
+#[repr(C)]
+struct Job {
+    link: DoubleLink,
+    data: u32,
+}
+
+#[test]
+fn test_derive_with_prelude_only() {
+    let mut list = LinkedList::<Job>::new();
+    let mut jobs = [1, 2, 3].map(|id| Job {
+        data: id,
+        ..Default::default()
+    });
+    for job in jobs.iter_mut() {
+        list.push(NonNull::from(job));
+    }
+
+    let ids = unsafe { list.iter().map(|n| *n.as_ref().data()).collect::<Vec<_>>() };
+    assert_eq!(ids, vec![3, 2, 1]);
+
+    unsafe {
+        let last = list.iter().last().unwrap();
+        assert_eq!(*last.as_ref().prev().unwrap().as_ref().data(), 2);
+    }
+}
@@ -126,10 +126,11 @@ pub fn node_derive(input: TokenStream) -> TokenStream {
         }
     };
 
-    let (link_ref, link_mut, data_ref, data_mut) = if is_union {
+    let (link_ref, link_mut, prev_link_mut, data_ref, data_mut) = if is_union {
         (
             quote! { unsafe { &self.link } },
             quote! { unsafe { &mut self.link } },
+            quote! { unsafe { &mut prev.link } },
             quote! { unsafe { &self.data } },
             quote! { unsafe { &mut self.data } },
         )
@@ -137,6 +138,7 @@ pub fn node_derive(input: TokenStream) -> TokenStream {
         (
             quote! { &self.link },
             quote! { &mut self.link },
+            quote! { &mut prev.link },
             quote! { &self.data },
             quote! { &mut self.data },
         )
@@ -161,6 +163,14 @@ pub fn node_derive(input: TokenStream) -> TokenStream {
         }
 
         impl #impl_generics #intrusive_path::traits::Node for #struct_name #ty_generics #where_clause {
+            const DETACHES_WITHOUT_PARENT: bool = #is_double_linked;
+
+            #[inline]
+            fn prev_node(&self) -> Option<::core::ptr::NonNull<Self::Target>> {
+                let link = #link_ref;
+                #intrusive_path::traits::Node::prev_node(link).map(|n| n.cast())
+            }
+
             #[inline]
             fn append_to<L>(&mut self, list: &mut L)
             where
@@ -173,6 +183,15 @@ pub fn node_derive(input: TokenStream) -> TokenStream {
                 }
             }
 
+            #[inline]
+            unsafe fn insert_after(&mut self, prev: &mut Self) {
+                unsafe {
+                    let link = #link_mut;
+                    let prev_link = #prev_link_mut;
+                    link.insert_after(prev_link);
+                }
+            }
+
             #[inline]
             unsafe fn detach<L>(&mut self, parent: Option<&mut L>)
             where